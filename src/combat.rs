@@ -0,0 +1,186 @@
+use inquire::{validator::Validation, Text};
+use serde::{Deserialize, Serialize};
+
+use crate::{error::Error, run::GamePlayed};
+
+/// Personal kills, deaths, assists and damage of the games recorded with them.
+///
+/// Assists and damage are optional on their own, so their averages are taken over the games
+/// they were recorded in.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct CombatStats {
+    /// Games recorded with at least kills and deaths.
+    pub games: usize,
+    pub kills: usize,
+    pub deaths: usize,
+    pub assists: usize,
+    pub assist_games: usize,
+    pub damage: usize,
+    pub damage_games: usize,
+}
+
+impl CombatStats {
+    /// Counts the combat stats of `game`, games without kills and deaths are skipped.
+    pub const fn add(&mut self, game: &GamePlayed) {
+        let (Some(kills), Some(deaths)) = (game.kills, game.deaths) else {
+            return;
+        };
+        self.games += 1;
+        self.kills += kills as usize;
+        self.deaths += deaths as usize;
+        if let Some(assists) = game.assists {
+            self.assists += assists as usize;
+            self.assist_games += 1;
+        }
+        if let Some(damage) = game.damage {
+            self.damage += damage as usize;
+            self.damage_games += 1;
+        }
+    }
+
+    /// Kills per death, a game without deaths counts its kills as is.
+    pub fn kd_ratio(&self) -> f32 {
+        self.kills as f32 / self.deaths.max(1) as f32
+    }
+
+    pub fn kills_per_game(&self) -> f32 {
+        per_game(self.kills, self.games)
+    }
+
+    pub fn deaths_per_game(&self) -> f32 {
+        per_game(self.deaths, self.games)
+    }
+
+    pub fn assists_per_game(&self) -> f32 {
+        per_game(self.assists, self.assist_games)
+    }
+
+    pub fn damage_per_game(&self) -> f32 {
+        per_game(self.damage, self.damage_games)
+    }
+}
+
+fn per_game(total: usize, games: usize) -> f32 {
+    if games == 0 {
+        return 0.0;
+    }
+    total as f32 / games as f32
+}
+
+/// Combat stats of a single game as written on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CombatLine {
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: Option<u32>,
+    pub damage: Option<u32>,
+}
+
+impl CombatLine {
+    pub const fn apply_to(self, game: &mut GamePlayed) {
+        game.kills = Some(self.kills);
+        game.deaths = Some(self.deaths);
+        game.assists = self.assists;
+        game.damage = self.damage;
+    }
+}
+
+/// Parses combat stats written as `kills/deaths[/assists[/damage]]`, e.g. `12/8/3/1500`.
+pub fn parse_combat_line(s: &str) -> Result<CombatLine, String> {
+    let numbers = s
+        .split('/')
+        .map(|n| {
+            n.trim()
+                .parse::<u32>()
+                .map_err(|_| format!("[{n}] is not a whole number"))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    match numbers[..] {
+        [kills, deaths] => Ok(CombatLine {
+            kills,
+            deaths,
+            assists: None,
+            damage: None,
+        }),
+        [kills, deaths, assists] => Ok(CombatLine {
+            kills,
+            deaths,
+            assists: Some(assists),
+            damage: None,
+        }),
+        [kills, deaths, assists, damage] => Ok(CombatLine {
+            kills,
+            deaths,
+            assists: Some(assists),
+            damage: Some(damage),
+        }),
+        _ => Err(format!(
+            "[{s}] is not kills/deaths, optionally followed by /assists and /damage"
+        )),
+    }
+}
+
+/// Asks for the kills, deaths, assists and damage of `game`, which is left without them when the
+/// answer is empty.
+pub fn prompt_combat(game: &mut GamePlayed) -> Result<(), Error> {
+    let answer = Text::new("Kills/deaths/assists/damage?")
+        .with_help_message("like 12/8/3/1500, assists and damage can be left off, empty to skip")
+        .with_validator(|s: &str| {
+            Ok(match s.trim() {
+                "" => Validation::Valid,
+                s => parse_combat_line(s)
+                    .map_or_else(|e| Validation::Invalid(e.into()), |_| Validation::Valid),
+            })
+        })
+        .prompt()?;
+    if let Some(line) = Some(answer.trim())
+        .filter(|s| !s.is_empty())
+        .and_then(|s| parse_combat_line(s).ok())
+    {
+        line.apply_to(game);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::map::GunfightMap;
+
+    #[test]
+    fn test_combat_stats() -> Result<()> {
+        // Arrange
+        let games = [
+            GamePlayed { did_win: true, map: GunfightMap::Shipment, kills: Some(12), deaths: Some(6), assists: Some(2), damage: Some(1500), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Rust, kills: Some(6), deaths: Some(6), ..Default::default() },
+            // Recorded without combat stats
+            GamePlayed { did_win: true, map: GunfightMap::Meat, ..Default::default() },
+        ];
+
+        // Act
+        let mut stats = CombatStats::default();
+        games.iter().for_each(|game| stats.add(game));
+
+        // Assert
+        assert_eq!(stats, CombatStats { games: 2, kills: 18, deaths: 12, assists: 2, assist_games: 1, damage: 1500, damage_games: 1 });
+        assert_eq!(stats.kd_ratio(), 1.5);
+        assert_eq!(stats.kills_per_game(), 9.0);
+        assert_eq!(stats.damage_per_game(), 1500.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_combat_line() -> Result<()> {
+        // Act / Assert
+        assert_eq!(parse_combat_line("12/8"), Ok(CombatLine { kills: 12, deaths: 8, assists: None, damage: None }));
+        assert_eq!(parse_combat_line("12 / 8 / 3 / 1500"), Ok(CombatLine { kills: 12, deaths: 8, assists: Some(3), damage: Some(1500) }));
+        assert!(parse_combat_line("12").is_err());
+        assert!(parse_combat_line("12/eight").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,65 @@
+use serde::Serialize;
+
+/// z score of a two sided 95 % confidence level.
+const Z_95: f64 = 1.96;
+
+/// Range the true win percentage most likely lies in, given how many games were played.
+///
+/// Uses the Wilson score interval, which stays sensible for small samples where a 2 - 0 record
+/// would otherwise read as a certain 100 %.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq)]
+pub struct WinInterval {
+    pub lower: f32,
+    pub upper: f32,
+}
+
+impl WinInterval {
+    /// 95 % interval of the win percentage, nothing is known yet without any games.
+    pub fn wilson(wins: usize, losses: usize) -> Self {
+        let games = (wins + losses) as f64;
+        if games == 0.0 {
+            return Self {
+                lower: 0.0,
+                upper: 100.0,
+            };
+        }
+
+        let p = wins as f64 / games;
+        let z2 = Z_95 * Z_95;
+        let denominator = 1.0 + z2 / games;
+        let center = p + z2 / (2.0 * games);
+        let margin = Z_95 * (p * (1.0 - p) / games + z2 / (4.0 * games * games)).sqrt();
+
+        Self {
+            lower: ((center - margin) / denominator * 100.0) as f32,
+            upper: ((center + margin) / denominator * 100.0) as f32,
+        }
+    }
+}
+
+impl std::fmt::Display for WinInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.0} - {:.0} %", self.lower, self.upper)
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_wilson_small_sample_ranks_below_large() {
+        // Arrange / Act
+        let two_and_oh = WinInterval::wilson(2, 0);
+        let sixty_forty = WinInterval::wilson(60, 40);
+
+        // Assert
+        assert_eq!(format!("{two_and_oh}"), "34 - 100 %");
+        assert_eq!(format!("{sixty_forty}"), "50 - 69 %");
+        assert!(two_and_oh.lower < sixty_forty.lower);
+        assert_eq!(WinInterval::wilson(0, 0), WinInterval { lower: 0.0, upper: 100.0 });
+    }
+}
@@ -0,0 +1,213 @@
+use std::{
+    io::{Read, Write},
+    str::FromStr,
+};
+
+use chrono::{DateTime, Local};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::Error, loadouts::parse_weapons, map::GunfightMap, rounds::validate_round_score,
+    run::GamePlayed, tags::parse_tags, CodVersion, GameResult,
+};
+
+/// One game as it is laid out in a spreadsheet.
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+struct CsvGame {
+    map: String,
+    result: String,
+    date_time: String,
+    #[serde(default)]
+    rounds_won: Option<u8>,
+    #[serde(default)]
+    rounds_lost: Option<u8>,
+    #[serde(default)]
+    overtime: Option<bool>,
+    #[serde(default)]
+    kills: Option<u32>,
+    #[serde(default)]
+    deaths: Option<u32>,
+    #[serde(default)]
+    assists: Option<u32>,
+    #[serde(default)]
+    damage: Option<u32>,
+    #[serde(default)]
+    teammate: Option<String>,
+    /// Comma separated.
+    #[serde(default)]
+    weapons: Option<String>,
+    /// Comma separated.
+    #[serde(default)]
+    tags: Option<String>,
+    #[serde(default)]
+    notes: Option<String>,
+}
+
+impl From<&GamePlayed> for CsvGame {
+    fn from(game: &GamePlayed) -> Self {
+        Self {
+            map: game.map.to_string(),
+            result: if game.did_win { "win" } else { "loss" }.to_string(),
+            date_time: game.date_time.to_rfc3339(),
+            rounds_won: game.rounds_won,
+            rounds_lost: game.rounds_lost,
+            overtime: game.round_score().map(|_| game.overtime),
+            kills: game.kills,
+            deaths: game.deaths,
+            assists: game.assists,
+            damage: game.damage,
+            teammate: game.teammate.clone(),
+            weapons: Some(game.weapons.join(", ")).filter(|w| !w.is_empty()),
+            tags: Some(game.tags.join(", ")).filter(|t| !t.is_empty()),
+            notes: game.notes.clone(),
+        }
+    }
+}
+
+/// A row of an imported file that could not be turned into a game.
+#[derive(Debug, PartialEq, Eq)]
+pub struct RejectedRow {
+    /// Line of the file, counting the header as line 1.
+    pub line: u64,
+    pub reason: String,
+}
+
+impl std::fmt::Display for RejectedRow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.reason)
+    }
+}
+
+/// Reads games from a CSV file with `map`, `result` and `date_time` columns.
+///
+/// Map names are matched case insensitively, rows that do not hold a valid game in
+/// `cod_version` are returned separately instead of failing the whole import.
+pub fn read_games(
+    reader: impl Read,
+    cod_version: &CodVersion,
+) -> Result<(Vec<GamePlayed>, Vec<RejectedRow>), Error> {
+    let mut games = Vec::new();
+    let mut rejected = Vec::new();
+
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+    let headers = reader.headers()?.clone();
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, csv::Position::line);
+        let parsed = record
+            .deserialize::<CsvGame>(Some(&headers))
+            .map_err(|e| e.to_string())
+            .and_then(|record| parse_game(&record, cod_version));
+        match parsed {
+            Ok(game) => games.push(game),
+            Err(reason) => rejected.push(RejectedRow { line, reason }),
+        }
+    }
+
+    Ok((games, rejected))
+}
+
+fn parse_game(record: &CsvGame, cod_version: &CodVersion) -> Result<GamePlayed, String> {
+    let map = GunfightMap::from_str(&record.map)
+        .map_err(|_| format!("[{}] is not a known map", record.map))?;
+    GunfightMap::validate(&map, cod_version).map_err(|e| e.to_string())?;
+    let result = GameResult::from_str(&record.result, true)
+        .map_err(|_| format!("[{}] is not a win or a loss", record.result))?;
+    let date_time = record
+        .date_time
+        .parse::<DateTime<Local>>()
+        .map_err(|e| format!("[{}] is not an RFC 3339 timestamp, {e}", record.date_time))?;
+
+    let did_win = result == GameResult::Win;
+    if let (Some(won), Some(lost)) = (record.rounds_won, record.rounds_lost) {
+        validate_round_score(did_win, won, lost, record.overtime.unwrap_or_default())
+            .map_err(|e| e.to_string())?;
+    }
+
+    Ok(GamePlayed {
+        map,
+        did_win,
+        date_time,
+        rounds_won: record.rounds_won,
+        rounds_lost: record.rounds_lost,
+        overtime: record.overtime.unwrap_or_default(),
+        kills: record.kills,
+        deaths: record.deaths,
+        assists: record.assists,
+        damage: record.damage,
+        teammate: record.teammate.clone(),
+        weapons: record
+            .weapons
+            .as_deref()
+            .map(|w| parse_weapons(w, &[]))
+            .unwrap_or_default(),
+        tags: record.tags.as_deref().map(parse_tags).unwrap_or_default(),
+        notes: record.notes.clone(),
+    })
+}
+
+/// Writes `games` as CSV with a header row.
+pub fn write_games<'a>(
+    writer: impl Write,
+    games: impl IntoIterator<Item = &'a GamePlayed>,
+) -> Result<(), Error> {
+    let mut writer = csv::Writer::from_writer(writer);
+    for game in games {
+        writer.serialize(CsvGame::from(game))?;
+    }
+    writer.flush().map_err(csv::Error::from)?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_read_games() -> Result<()> {
+        // Arrange
+        let file = "map,result,date_time\n\
+                    shipment,Win,2023-12-01T20:00:00-05:00\n\
+                    ASILE9,loss,2023-12-01T20:10:00-05:00\n\
+                    Meat,l,2023-12-01T20:20:00-05:00\n\
+                    Nuketown,win,2023-12-01T20:30:00-05:00\n";
+
+        // Act
+        let (games, rejected) = read_games(file.as_bytes(), &CodVersion::MW3)?;
+
+        // Assert
+        assert_eq!(games, vec![
+            GamePlayed { map: GunfightMap::Shipment, did_win: true, date_time: "2023-12-01T20:00:00-05:00".parse()?, ..Default::default() },
+            GamePlayed { map: GunfightMap::Meat, did_win: false, date_time: "2023-12-01T20:20:00-05:00".parse()?, ..Default::default() },
+        ]);
+        assert_eq!(rejected.iter().map(|r| r.line).collect::<Vec<_>>(), vec![3, 5]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_then_read_games() -> Result<()> {
+        // Arrange
+        let games = vec![
+            GamePlayed { map: GunfightMap::Rust, did_win: true, date_time: "2023-12-01T20:00:00-05:00".parse()?, ..Default::default() },
+            GamePlayed { map: GunfightMap::Alley, did_win: false, date_time: "2023-12-01T20:10:00-05:00".parse()?, ..Default::default() },
+        ];
+        let mut file = Vec::new();
+
+        // Act
+        write_games(&mut file, &games)?;
+        let (read, rejected) = read_games(file.as_slice(), &CodVersion::MW3)?;
+
+        // Assert
+        assert_eq!(read, games);
+        assert!(rejected.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone};
+use chrono_tz::Tz;
+
+/// Decides which gaming night a game belongs to.
+///
+/// Days start at `start_hour` instead of midnight, so a session running past midnight stays on
+/// one day, and are counted in `timezone` when it is set instead of the clock of this machine.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DayBoundary {
+    pub start_hour: u32,
+    pub timezone: Option<Tz>,
+}
+
+impl DayBoundary {
+    pub const fn new(start_hour: u32, timezone: Option<Tz>) -> Self {
+        Self {
+            start_hour,
+            timezone,
+        }
+    }
+
+    /// The day `date_time` counts towards.
+    pub fn day_of(&self, date_time: &DateTime<Local>) -> NaiveDate {
+        (self.wall_clock(date_time) - Duration::hours(i64::from(self.start_hour))).date()
+    }
+
+    /// What the clock showed when `date_time` happened, in `timezone` when it is set.
+    pub fn wall_clock(&self, date_time: &DateTime<Local>) -> NaiveDateTime {
+        self.timezone.map_or_else(
+            || date_time.naive_local(),
+            |timezone| date_time.with_timezone(&timezone).naive_local(),
+        )
+    }
+
+    /// When `day` starts.
+    pub fn start_of(&self, day: NaiveDate) -> DateTime<Local> {
+        let start =
+            day.and_time(NaiveTime::from_hms_opt(self.start_hour, 0, 0).unwrap_or(NaiveTime::MIN));
+        self.timezone
+            .map_or_else(
+                || first_after(&Local, &start),
+                |timezone| first_after(&timezone, &start).map(|d| d.with_timezone(&Local)),
+            )
+            // Only at the ends of the calendar, where there is no clock to go by.
+            .unwrap_or_else(|| Local.from_utc_datetime(&start))
+    }
+}
+
+/// The first instant the wall clock of `timezone` shows `wall_clock`, or the hour after it when
+/// the clock skips it.
+fn first_after<T: TimeZone>(timezone: &T, wall_clock: &NaiveDateTime) -> Option<DateTime<T>> {
+    (0..=24)
+        .filter_map(|hours| wall_clock.checked_add_signed(Duration::hours(hours)))
+        .find_map(|wall_clock| earliest(timezone, &wall_clock))
+}
+
+/// The first instant the wall clock of `timezone` shows `wall_clock`, `None` when it is skipped.
+fn earliest<T: TimeZone>(timezone: &T, wall_clock: &NaiveDateTime) -> Option<DateTime<T>> {
+    timezone.from_local_datetime(wall_clock).earliest()
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_day_of_after_midnight() -> Result<()> {
+        // Arrange
+        let boundary = DayBoundary::new(4, Some(chrono_tz::America::New_York));
+        let before_midnight: DateTime<Local> = "2024-01-05T23:30:00-05:00".parse()?;
+        let after_midnight: DateTime<Local> = "2024-01-06T02:30:00-05:00".parse()?;
+        let next_morning: DateTime<Local> = "2024-01-06T04:00:00-05:00".parse()?;
+        // Recorded while traveling, 01:00 in New York.
+        let abroad: DateTime<Local> = "2024-01-06T07:00:00+01:00".parse()?;
+
+        // Act / Assert
+        let night = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+        assert_eq!(boundary.day_of(&before_midnight), night);
+        assert_eq!(boundary.day_of(&after_midnight), night);
+        assert_eq!(boundary.day_of(&abroad), night);
+        assert_eq!(boundary.day_of(&next_morning), night.succ_opt().unwrap());
+        assert_eq!(boundary.start_of(night.succ_opt().unwrap()), next_morning);
+        Ok(())
+    }
+
+    #[test]
+    fn test_start_of_skipped_hour() -> Result<()> {
+        // Arrange
+        // Clocks in New York went from 02:00 straight to 03:00 that night.
+        let boundary = DayBoundary::new(2, Some(chrono_tz::America::New_York));
+        let spring_forward = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        let three_am: DateTime<Local> = "2024-03-10T03:00:00-04:00".parse()?;
+
+        // Act
+        let start = boundary.start_of(spring_forward);
+
+        // Assert
+        assert_eq!(start, three_am);
+        assert_eq!(boundary.day_of(&start), spring_forward);
+        Ok(())
+    }
+}
@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use inquire::InquireError;
 use thiserror::Error;
 
-use crate::map::GunfightMap;
+use crate::{map::GunfightMap, CodVersion};
 
 #[derive(Error, Debug)]
 pub enum Error {
@@ -18,4 +18,67 @@ pub enum Error {
 
     #[error("could not find stats file at [{0:?}]")]
     StatsFileNotFound(PathBuf),
+
+    #[error("could not read stats file at [{path:?}]. {source}")]
+    ReadStats {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("could not parse stats file at [{path:?}] {context}. {source}")]
+    ParseStats {
+        path: PathBuf,
+        line: usize,
+        column: usize,
+        context: String,
+        source: serde_json::Error,
+    },
+
+    #[error("stats file is neither a list of games nor has a `schema_version`")]
+    UnknownStatsSchema,
+
+    #[error("stats file has schema version {found}, this version only supports up to {supported}")]
+    UnsupportedStatsSchema { found: u64, supported: u64 },
+
+    #[error("could not write stats file at [{path:?}]. {source}")]
+    WriteStats {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("could not read or write csv. {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("stats database error. {0}")]
+    Database(#[from] rusqlite::Error),
+
+    #[error("could not serialize games. {0}")]
+    SerializeStats(serde_json::Error),
+
+    #[error("could not write stats as yaml. {0}")]
+    Yaml(#[from] serde_yaml::Error),
+
+    #[error("map [{0}] is not playable in [{1}]")]
+    MapNotInVersion(GunfightMap, CodVersion),
+
+    #[error("season [{0}] is not known for [{1}]")]
+    SeasonNotFound(usize, CodVersion),
+
+    #[error("a round score of {won} - {lost} is not possible for a {}", if *did_win { "win" } else { "loss" })]
+    InvalidRoundScore { won: u8, lost: u8, did_win: bool },
+
+    #[error("only a game decided in the last round can go to overtime, not a {won} - {lost}")]
+    InvalidOvertime { won: u8, lost: u8 },
+
+    #[error("could not find a game matching [{0}]")]
+    GameNotFound(String),
+
+    #[error("no games have been recorded yet")]
+    NoGamesRecorded,
+
+    #[error("no games recorded for [{0}] to graph")]
+    NoGamesToGraph(CodVersion),
+
+    #[error("failed drawing graph. {0}")]
+    FailedDrawingGraph(String),
 }
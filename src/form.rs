@@ -0,0 +1,88 @@
+use crate::run::GamePlayed;
+
+/// Numbers of most recent games the form is taken over.
+pub const FORM_WINDOWS: [usize; 3] = [10, 25, 50];
+
+/// Points the form has to be off the lifetime win percentage to count as up or down.
+const FORM_DEAD_BAND: f32 = 1.0;
+
+/// Win percentage over the last few games.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Form {
+    pub games: usize,
+    pub win_percentage: f32,
+}
+
+impl Form {
+    /// Form over the last `window` games of `games`, ordered oldest first. `None` until that
+    /// many games have been played.
+    pub fn over(games: &[&GamePlayed], window: usize) -> Option<Self> {
+        if window == 0 || games.len() < window {
+            return None;
+        }
+        let wins = games[games.len() - window..]
+            .iter()
+            .filter(|g| g.did_win)
+            .count();
+        Some(Self {
+            games: window,
+            win_percentage: wins as f32 / window as f32 * 100.0,
+        })
+    }
+
+    /// Form over each of the `FORM_WINDOWS` that has been played.
+    pub fn recent(games: &[&GamePlayed]) -> Vec<Self> {
+        FORM_WINDOWS
+            .iter()
+            .filter_map(|window| Self::over(games, *window))
+            .collect()
+    }
+
+    /// Whether the form is above, below or about the same as `lifetime_win_percentage`.
+    pub fn arrow(&self, lifetime_win_percentage: f32) -> &'static str {
+        let difference = self.win_percentage - lifetime_win_percentage;
+        if difference > FORM_DEAD_BAND {
+            "↑"
+        } else if difference < -FORM_DEAD_BAND {
+            "↓"
+        } else {
+            "→"
+        }
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Duration, Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::map::GunfightMap;
+
+    #[test]
+    fn test_recent_form() -> Result<()> {
+        // Arrange
+        let start = Local.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        // Lost the first 20, won 7 of the last 10.
+        let games = (0..30)
+            .map(|i| GamePlayed { did_win: i >= 20 && i % 10 < 7, map: GunfightMap::Shipment, date_time: start + Duration::minutes(i), ..Default::default() })
+            .collect::<Vec<_>>();
+        let games = games.iter().collect::<Vec<_>>();
+
+        // Act
+        let form = Form::recent(&games);
+
+        // Assert
+        assert_eq!(form, vec![
+            Form { games: 10, win_percentage: 70.0 },
+            Form { games: 25, win_percentage: 28.0 },
+        ]);
+        assert_eq!(form[0].arrow(7.0 / 30.0 * 100.0), "↑");
+        assert_eq!(form[1].arrow(28.5), "→");
+        assert_eq!(form[1].arrow(50.0), "↓");
+        Ok(())
+    }
+}
@@ -1,68 +1,487 @@
-// use serde::Deserialize;
-
-// #[derive(Deserialize)]
-// struct DailyData {
-//     #[serde(default)]
-//     new_cases: f64,
-//     #[serde(default)]
-//     total_cases: f64,
-// }
-//
-// #[derive(Deserialize)]
-// struct CountryData {
-//     data: Vec<DailyData>,
-// }
-
-// const OUT_FILE_NAME: &str = "plotters-doc-data/win_loss.svg";
-// pub fn draw_graph(games: &[GamePlayed]) -> Result<()> {
-//     let root = SVGBackend::new(OUT_FILE_NAME, (1024, 768)).into_drawing_area();
-//     root.fill(&WHITE)?;
-//
-//     let (upper, lower) = root.split_vertically(750);
-//
-//     lower.titled(
-//         "Win Loss",
-//         ("sans-serif", 10).into_font().color(&BLACK.mix(0.5)),
-//     )?;
-//
-//     let mut chart = ChartBuilder::on(&upper)
-//         .caption("Win Loss", ("sans-serif", (5).percent_height()))
-//         .set_label_area_size(LabelAreaPosition::Left, (8).percent())
-//         .set_label_area_size(LabelAreaPosition::Bottom, (4).percent())
-//         .margin((1).percent())
-//         .build_cartesian_2d(
-//             (20u32..5000_0000u32)
-//                 .log_scale()
-//                 .with_key_points(vec![50, 100, 1000, 10000, 100000, 1000000, 10000000]),
-//             (0u32..50_0000u32)
-//                 .log_scale()
-//                 .with_key_points(vec![10, 50, 100, 1000, 10000, 100000, 200000]),
-//         )?;
-//
-//     chart
-//         .configure_mesh()
-//         .x_desc("Time")
-//         .y_desc("Win")
-//         .draw()?;
-//
-//     let data: std::collections::HashMap<GunfightMap, bool> = games.iter().map(|g| (g.map, g.did_win)).collect::<HashMap<_, _>>();
-//     for (idx, series) in GunfightMap::iter()
-//         .enumerate()
-//     {
-//         let color = Palette99::pick(idx).mix(0.9);
-//         chart
-//             .draw_series(LineSeries::new(
-//                 data[series],
-//                 color.stroke_width(3),
-//             ))?
-//             .label(series)
-//             .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
-//     }
-//
-//     chart.configure_series_labels().border_style(BLACK).draw()?;
-//
-//     // To avoid the IO failure being ignored silently, we manually call the present function
-//     root.present().expect("Unable to write result to file, please make sure 'plotters-doc-data' dir exists under current dir");
-//     println!("Result has been saved to {}", OUT_FILE_NAME);
-//     Ok(())
-// }
+use std::path::Path;
+
+use chrono::{DateTime, Duration, Local};
+use plotters::{
+    coord::Shift,
+    prelude::*,
+    style::text_anchor::{HPos, Pos, VPos},
+};
+use tracing::instrument;
+
+use crate::{
+    error::Error,
+    form::FORM_WINDOWS,
+    run::GamePlayed,
+    stats::{filter_by_version, StatsGroup},
+    time_of_day::{TimeOfDayStats, HOURS, WEEKDAYS},
+    CodVersion, DAY_FMT,
+};
+
+pub const WIN_RATE_OUT_FILE: &str = "plotters-doc-data/win_loss.png";
+pub const MAP_STATS_OUT_FILE: &str = "plotters-doc-data/map_win_loss.png";
+pub const TIME_OF_DAY_OUT_FILE: &str = "plotters-doc-data/time_of_day.png";
+
+const GRAPH_SIZE: (u32, u32) = (1024, 768);
+const MAP_GRAPH_SIZE: (u32, u32) = (1280, 768);
+const HEATMAP_SIZE: (u32, u32) = (1440, 600);
+const ROLLING_COLORS: [RGBColor; 4] = [RED, GREEN, MAGENTA, CYAN];
+
+/// Win percentage at the time of each game.
+type WinPercentageLine = Vec<(DateTime<Local>, f64)>;
+
+/// Draws the cumulative and rolling win percentage of every game played in `cod_version`,
+/// along with the form over each of the `FORM_WINDOWS` other than `window`.
+///
+/// The backend is picked from the extension of `out_file`, `.svg` files are drawn as vectors
+/// and everything else is rendered as a bitmap.
+#[instrument(skip(games))]
+pub fn draw_win_rate_graph(
+    games: &[GamePlayed],
+    cod_version: &CodVersion,
+    out_file: &Path,
+    window: usize,
+) -> Result<(), Error> {
+    let games = filter_by_version(games, cod_version);
+    if games.is_empty() {
+        return Err(Error::NoGamesToGraph(cod_version.clone()));
+    }
+
+    create_out_dir(out_file)?;
+
+    let cumulative = cumulative_win_percentage(&games);
+    let rolling = std::iter::once(window)
+        .chain(FORM_WINDOWS.into_iter().filter(|w| *w != window))
+        .map(|w| (w, rolling_win_percentage(&games, w)))
+        .filter(|(_, line)| !line.is_empty())
+        .collect::<Vec<_>>();
+    let caption = format!("Win % over time ({cod_version})");
+
+    if is_svg(out_file) {
+        let root = SVGBackend::new(out_file, GRAPH_SIZE).into_drawing_area();
+        draw_win_rate(&root, &caption, &cumulative, &rolling)?;
+    } else {
+        let root = BitMapBackend::new(out_file, GRAPH_SIZE).into_drawing_area();
+        draw_win_rate(&root, &caption, &cumulative, &rolling)?;
+    }
+
+    println!("Graph has been saved to {}", out_file.to_string_lossy());
+    Ok(())
+}
+
+fn draw_win_rate<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    caption: &str,
+    cumulative: &[(DateTime<Local>, f64)],
+    rolling: &[(usize, WinPercentageLine)],
+) -> Result<(), Error> {
+    let (Some(first), Some(last)) = (cumulative.first(), cumulative.last()) else {
+        return Ok(());
+    };
+    // A single day of games would otherwise give the chart an empty x range.
+    let end = if last.0 > first.0 {
+        last.0
+    } else {
+        first.0 + Duration::hours(1)
+    };
+
+    root.fill(&WHITE).map_err(graph_error)?;
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, ("sans-serif", (5).percent_height()))
+        .set_label_area_size(LabelAreaPosition::Left, (8).percent())
+        .set_label_area_size(LabelAreaPosition::Bottom, (6).percent())
+        .margin((1).percent())
+        .build_cartesian_2d(first.0..end, 0f64..100f64)
+        .map_err(graph_error)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Date")
+        .y_desc("Win %")
+        .x_label_formatter(&|d| d.format(DAY_FMT).to_string())
+        .draw()
+        .map_err(graph_error)?;
+
+    chart
+        .draw_series(LineSeries::new(
+            cumulative.iter().copied(),
+            BLUE.stroke_width(3),
+        ))
+        .map_err(graph_error)?
+        .label("Cumulative")
+        .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], BLUE.filled()));
+
+    // The first line is the window asked for, the form lines after it are drawn lighter.
+    for (i, (window, line)) in rolling.iter().enumerate() {
+        let color = ROLLING_COLORS[i % ROLLING_COLORS.len()];
+        let style = if i == 0 {
+            color.stroke_width(2)
+        } else {
+            color.mix(0.6).stroke_width(1)
+        };
+        chart
+            .draw_series(LineSeries::new(line.iter().copied(), style))
+            .map_err(graph_error)?
+            .label(format!("Last {window} games"))
+            .legend(move |(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], color.filled()));
+    }
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::LowerRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(graph_error)?;
+
+    // To avoid the IO failure being ignored silently, we manually call the present function
+    root.present().map_err(graph_error)
+}
+
+/// Draws the wins and losses of every map side by side, ordered by win percentage.
+///
+/// The best map is drawn on top.
+#[instrument(skip(stats))]
+pub fn draw_map_graph(
+    stats: &StatsGroup,
+    cod_version: &CodVersion,
+    out_file: &Path,
+) -> Result<(), Error> {
+    let (labels, bars) = map_bars(stats);
+    if labels.is_empty() {
+        return Err(Error::NoGamesToGraph(cod_version.clone()));
+    }
+
+    create_out_dir(out_file)?;
+
+    let caption = format!("Wins and losses per map ({cod_version})");
+
+    if is_svg(out_file) {
+        let root = SVGBackend::new(out_file, MAP_GRAPH_SIZE).into_drawing_area();
+        draw_map_bars(&root, &caption, &labels, &bars)?;
+    } else {
+        let root = BitMapBackend::new(out_file, MAP_GRAPH_SIZE).into_drawing_area();
+        draw_map_bars(&root, &caption, &labels, &bars)?;
+    }
+
+    println!("Graph has been saved to {}", out_file.to_string_lossy());
+    Ok(())
+}
+
+/// A label with the win percentage and the wins and losses of every map, best map first.
+fn map_bars(stats: &StatsGroup) -> (Vec<String>, Vec<(usize, usize)>) {
+    stats
+        .get_sorted_map_stats()
+        .into_iter()
+        .map(|(map, map_stats)| {
+            (
+                format!("{map} ({:.0}%)", map_stats.get_win_percentage()),
+                (map_stats.wins, map_stats.losses),
+            )
+        })
+        .unzip()
+}
+
+fn draw_map_bars<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    caption: &str,
+    labels: &[String],
+    bars: &[(usize, usize)],
+) -> Result<(), Error> {
+    let max = bars
+        .iter()
+        .map(|(wins, losses)| *wins.max(losses))
+        .max()
+        .unwrap_or_default();
+    // Rows are drawn bottom up, flip them so the best map ends up on top.
+    let row = |i: usize| labels.len() - 1 - i;
+
+    root.fill(&WHITE).map_err(graph_error)?;
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, ("sans-serif", (5).percent_height()))
+        .set_label_area_size(LabelAreaPosition::Left, (18).percent())
+        .set_label_area_size(LabelAreaPosition::Bottom, (6).percent())
+        .margin((1).percent())
+        .build_cartesian_2d(0usize..max + 1, (0..labels.len() - 1).into_segmented())
+        .map_err(graph_error)?;
+
+    chart
+        .configure_mesh()
+        .disable_y_mesh()
+        .x_desc("Games")
+        .y_labels(labels.len())
+        .y_label_formatter(&|y| match y {
+            SegmentValue::CenterOf(i) => labels.get(row(*i)).cloned().unwrap_or_default(),
+            _ => String::new(),
+        })
+        .draw()
+        .map_err(graph_error)?;
+
+    chart
+        .draw_series(bars.iter().enumerate().map(|(i, (wins, _))| {
+            let mut bar = Rectangle::new(
+                [
+                    (0, SegmentValue::Exact(row(i) + 1)),
+                    (*wins, SegmentValue::CenterOf(row(i))),
+                ],
+                GREEN.filled(),
+            );
+            bar.set_margin(4, 0, 0, 0);
+            bar
+        }))
+        .map_err(graph_error)?
+        .label("Wins")
+        .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], GREEN.filled()));
+
+    chart
+        .draw_series(bars.iter().enumerate().map(|(i, (_, losses))| {
+            let mut bar = Rectangle::new(
+                [
+                    (0, SegmentValue::CenterOf(row(i))),
+                    (*losses, SegmentValue::Exact(row(i))),
+                ],
+                RED.filled(),
+            );
+            bar.set_margin(0, 4, 0, 0);
+            bar
+        }))
+        .map_err(graph_error)?
+        .label("Losses")
+        .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 10, y + 5)], RED.filled()));
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::LowerRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()
+        .map_err(graph_error)?;
+
+    root.present().map_err(graph_error)
+}
+
+/// Draws the win percentage of every hour on every weekday as a heatmap, from red for hours we
+/// mostly lose to green for hours we mostly win. Hours without games are left grey.
+#[instrument(skip(stats))]
+pub fn draw_time_of_day_graph(
+    stats: &TimeOfDayStats,
+    cod_version: &CodVersion,
+    out_file: &Path,
+) -> Result<(), Error> {
+    if stats.by_hour().is_empty() {
+        return Err(Error::NoGamesToGraph(cod_version.clone()));
+    }
+
+    create_out_dir(out_file)?;
+
+    let caption = format!("Win % per weekday and hour ({cod_version})");
+
+    if is_svg(out_file) {
+        let root = SVGBackend::new(out_file, HEATMAP_SIZE).into_drawing_area();
+        draw_heatmap(&root, &caption, stats)?;
+    } else {
+        let root = BitMapBackend::new(out_file, HEATMAP_SIZE).into_drawing_area();
+        draw_heatmap(&root, &caption, stats)?;
+    }
+
+    println!("Graph has been saved to {}", out_file.to_string_lossy());
+    Ok(())
+}
+
+fn draw_heatmap<DB: DrawingBackend>(
+    root: &DrawingArea<DB, Shift>,
+    caption: &str,
+    stats: &TimeOfDayStats,
+) -> Result<(), Error> {
+    // Columns start at the hour days start at, so a night past midnight stays in one piece.
+    let hour = |column: usize| (column + stats.start_hour()) % HOURS;
+    // Rows are drawn bottom up, flip them so Monday ends up on top.
+    let row = |i: usize| WEEKDAYS.len() - 1 - i;
+
+    root.fill(&WHITE).map_err(graph_error)?;
+
+    let mut chart = ChartBuilder::on(root)
+        .caption(caption, ("sans-serif", (6).percent_height()))
+        .set_label_area_size(LabelAreaPosition::Left, (6).percent())
+        .set_label_area_size(LabelAreaPosition::Bottom, (8).percent())
+        .margin((1).percent())
+        .build_cartesian_2d(
+            (0..HOURS - 1).into_segmented(),
+            (0..WEEKDAYS.len() - 1).into_segmented(),
+        )
+        .map_err(graph_error)?;
+
+    chart
+        .configure_mesh()
+        .disable_mesh()
+        .x_desc("Hour")
+        .x_labels(HOURS)
+        .y_labels(WEEKDAYS.len())
+        .x_label_formatter(&|x| match x {
+            SegmentValue::CenterOf(column) => format!("{:02}", hour(*column)),
+            _ => String::new(),
+        })
+        .y_label_formatter(&|y| match y {
+            SegmentValue::CenterOf(i) => WEEKDAYS
+                .get(row(*i))
+                .map(ToString::to_string)
+                .unwrap_or_default(),
+            _ => String::new(),
+        })
+        .draw()
+        .map_err(graph_error)?;
+
+    let cells = WEEKDAYS
+        .iter()
+        .enumerate()
+        .flat_map(|(i, weekday)| (0..HOURS).map(move |column| (i, column, *weekday)))
+        .map(|(i, column, weekday)| (row(i), column, stats.get(weekday, hour(column))))
+        .collect::<Vec<_>>();
+
+    chart
+        .draw_series(cells.iter().map(|(y, x, map_stats)| {
+            let color = if map_stats.wins + map_stats.losses == 0 {
+                RGBColor(230, 230, 230)
+            } else {
+                win_percentage_color(map_stats.get_win_percentage())
+            };
+            let mut cell = Rectangle::new(
+                [
+                    (SegmentValue::Exact(*x), SegmentValue::Exact(*y)),
+                    (SegmentValue::Exact(x + 1), SegmentValue::Exact(y + 1)),
+                ],
+                color.filled(),
+            );
+            cell.set_margin(1, 1, 1, 1);
+            cell
+        }))
+        .map_err(graph_error)?;
+
+    let label_style = TextStyle::from(("sans-serif", 14)).pos(Pos::new(HPos::Center, VPos::Center));
+    chart
+        .draw_series(
+            cells
+                .iter()
+                .filter(|(_, _, map_stats)| map_stats.wins + map_stats.losses > 0)
+                .map(|(y, x, map_stats)| {
+                    Text::new(
+                        format!("{}-{}", map_stats.wins, map_stats.losses),
+                        (SegmentValue::CenterOf(*x), SegmentValue::CenterOf(*y)),
+                        label_style.clone(),
+                    )
+                }),
+        )
+        .map_err(graph_error)?;
+
+    root.present().map_err(graph_error)
+}
+
+/// Red at 0 %, yellow at 50 % and green at 100 %.
+fn win_percentage_color(win_percentage: f32) -> RGBColor {
+    let p = f64::from(win_percentage.clamp(0.0, 100.0)) / 100.0;
+    let red = (2.0 * (1.0 - p)).min(1.0);
+    let green = (2.0 * p).min(1.0);
+    RGBColor((red * 220.0) as u8, (green * 180.0) as u8, 60)
+}
+
+fn create_out_dir(out_file: &Path) -> Result<(), Error> {
+    if let Some(parent) = out_file.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| Error::FailedDrawingGraph(e.to_string()))?;
+    }
+    Ok(())
+}
+
+fn graph_error<E: std::error::Error + Send + Sync>(error: DrawingAreaErrorKind<E>) -> Error {
+    Error::FailedDrawingGraph(error.to_string())
+}
+
+fn is_svg(out_file: &Path) -> bool {
+    out_file
+        .extension()
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("svg"))
+}
+
+/// Win percentage of all games played up to and including each game.
+pub fn cumulative_win_percentage(games: &[&GamePlayed]) -> Vec<(DateTime<Local>, f64)> {
+    let mut wins = 0;
+    games
+        .iter()
+        .enumerate()
+        .map(|(i, game)| {
+            if game.did_win {
+                wins += 1;
+            }
+            (game.date_time, f64::from(wins) * 100.0 / (i + 1) as f64)
+        })
+        .collect()
+}
+
+/// Win percentage of the last `window` games, starting once `window` games have been played.
+pub fn rolling_win_percentage(games: &[&GamePlayed], window: usize) -> Vec<(DateTime<Local>, f64)> {
+    if window == 0 {
+        return Vec::new();
+    }
+    games
+        .windows(window)
+        .map(|w| {
+            let wins = w.iter().filter(|g| g.did_win).count();
+            (
+                w[w.len() - 1].date_time,
+                wins as f64 * 100.0 / window as f64,
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use crate::GunfightMap;
+
+    use super::*;
+
+    #[test]
+    fn test_cumulative_and_rolling_win_percentage() {
+        // Arrange
+        let games: Vec<GamePlayed> = vec![
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 3).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 4).unwrap(), ..Default::default() },
+        ];
+        let games = games.iter().collect::<Vec<_>>();
+
+        // Act
+        let cumulative = cumulative_win_percentage(&games).into_iter().map(|p| p.1).collect::<Vec<_>>();
+        let rolling = rolling_win_percentage(&games, 2).into_iter().map(|p| p.1).collect::<Vec<_>>();
+
+        // Assert
+        assert_eq!(cumulative, vec![100.0, 50.0, 200.0 / 3.0, 75.0]);
+        assert_eq!(rolling, vec![50.0, 50.0, 100.0]);
+    }
+
+    #[test]
+    fn test_map_bars() {
+        // Arrange
+        let game = |did_win, map| GamePlayed { did_win, map, ..Default::default() };
+        let games = [
+            game(true, GunfightMap::Rust),
+            game(false, GunfightMap::Rust),
+            game(true, GunfightMap::Shipment),
+            game(false, GunfightMap::Meat),
+        ];
+        let stats = StatsGroup::from_games(&games);
+
+        // Act
+        let (labels, bars) = map_bars(&stats);
+
+        // Assert
+        assert_eq!(labels, vec!["Shipment (100%)", "Rust (50%)", "Meat (0%)"]);
+        assert_eq!(bars, vec![(1, 0), (1, 1), (0, 1)]);
+        assert_eq!(map_bars(&StatsGroup::from_games(&[])), (vec![], vec![]));
+    }
+}
@@ -0,0 +1,261 @@
+use std::{
+    fs::File,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use tracing::instrument;
+
+use crate::{
+    error::Error,
+    run::GamePlayed,
+    schema::{self, StatSheet},
+    store::GameStore,
+};
+
+/// Stores every game in a single pretty printed JSON file.
+///
+/// The whole file is rewritten on every change, see [`write_atomically`].
+#[derive(Debug)]
+pub struct JsonStore {
+    file_path: PathBuf,
+    games: Vec<GamePlayed>,
+}
+
+impl JsonStore {
+    /// Loads every game from `file_path`, upgrading the file in place when it was written with an
+    /// older schema.
+    #[instrument]
+    pub fn open(file_path: &Path) -> Result<Self, Error> {
+        tracing::info!(stats_path=?file_path, "checking if file exists");
+        if !file_path.exists() {
+            tracing::error!(stats_path=?file_path, "file does not exist");
+            return Err(Error::StatsFileNotFound(file_path.to_path_buf()));
+        }
+
+        tracing::trace!(stats_path=?file_path, "loading file data");
+        let contents = std::fs::read_to_string(file_path).map_err(|source| Error::ReadStats {
+            path: file_path.to_path_buf(),
+            source,
+        })?;
+        let raw: serde_json::Value = serde_json::from_str(&contents)
+            .map_err(|source| parse_error(file_path, &contents, None, source))?;
+        let (sheet, found) = schema::migrate(raw)?;
+        let migrated = found != schema::SCHEMA_VERSION;
+
+        // Re-parse the file itself when it was not migrated, so errors keep their line and column.
+        let parsed = if migrated {
+            serde_json::from_value::<StatSheet>(sheet.clone())
+        } else {
+            serde_json::from_str::<StatSheet>(&contents)
+        };
+        let games = parsed
+            .map_err(|source| parse_error(file_path, &contents, Some(&sheet), source))?
+            .games
+            .into_owned();
+        tracing::trace!(stats_path=?file_path, "file data loaded");
+
+        let mut store = Self {
+            file_path: file_path.to_path_buf(),
+            games,
+        };
+        if migrated {
+            store.save()?;
+            tracing::info!(stats_path=?file_path, from=found, to=schema::SCHEMA_VERSION, "upgraded stats file");
+        } else {
+            store.games.sort_by_key(|g| g.date_time);
+        }
+        Ok(store)
+    }
+
+    #[instrument(skip(self), fields(stats_path=?self.file_path))]
+    fn save(&mut self) -> Result<(), Error> {
+        tracing::debug!("sorting games");
+        self.games.sort_by_key(|g| g.date_time);
+        let contents = serde_json::to_vec_pretty(&StatSheet::new(&self.games))
+            .map_err(Error::SerializeStats)?;
+        tracing::trace!("writing to file");
+        write_atomically(&self.file_path, &contents).map_err(|source| Error::WriteStats {
+            path: self.file_path.clone(),
+            source,
+        })?;
+        tracing::trace!("wrote to file");
+        Ok(())
+    }
+}
+
+impl GameStore for JsonStore {
+    fn load_all(&mut self) -> Result<Vec<GamePlayed>, Error> {
+        Ok(self.games.clone())
+    }
+
+    fn append(&mut self, game: &GamePlayed) -> Result<(), Error> {
+        self.games.push(game.clone());
+        self.save()
+    }
+
+    fn append_all(&mut self, games: &[GamePlayed]) -> Result<(), Error> {
+        self.games.extend_from_slice(games);
+        self.save()
+    }
+
+    fn delete(&mut self, game: &GamePlayed) -> Result<bool, Error> {
+        let Some(index) = self.games.iter().position(|g| g == game) else {
+            return Ok(false);
+        };
+        self.games.remove(index);
+        self.save()?;
+        Ok(true)
+    }
+
+    fn replace(&mut self, old: &GamePlayed, new: &GamePlayed) -> Result<bool, Error> {
+        let Some(index) = self.games.iter().position(|g| g == old) else {
+            return Ok(false);
+        };
+        self.games[index] = new.clone();
+        self.save()?;
+        Ok(true)
+    }
+}
+
+/// Writes `contents` to a temp file next to `file_path` and only renames it over the original
+/// once it is synced to disk, so a crash mid-write can never truncate the stat sheet.
+///
+/// The previous version of the file is kept as a `.bak` next to it.
+fn write_atomically(file_path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let tmp_path = with_suffix(file_path, ".tmp");
+    let written = File::create(&tmp_path).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(error) = written {
+        // Best effort, the original file has not been touched at this point.
+        let _ = std::fs::remove_file(&tmp_path);
+        return Err(error);
+    }
+
+    if file_path.exists() {
+        std::fs::copy(file_path, with_suffix(file_path, ".bak"))?;
+    }
+    std::fs::rename(&tmp_path, file_path)?;
+
+    // The rename is only durable once the directory entry itself has been synced.
+    #[cfg(unix)]
+    {
+        let dir = file_path
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        File::open(dir)?.sync_all()?;
+    }
+    Ok(())
+}
+
+fn with_suffix(file_path: &Path, suffix: &str) -> PathBuf {
+    let mut path = file_path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
+
+/// Builds an error that points at the game, and the line of the file, that could not be parsed.
+fn parse_error(
+    file_path: &Path,
+    contents: &str,
+    sheet: Option<&serde_json::Value>,
+    source: serde_json::Error,
+) -> Error {
+    // When the file is valid json the error is in one of the records, find out which one.
+    let record = sheet
+        .and_then(|sheet| sheet.get("games"))
+        .and_then(serde_json::Value::as_array)
+        .and_then(|records| {
+            records
+                .iter()
+                .position(|r| GamePlayed::deserialize(r).is_err())
+        });
+    // Errors from an already parsed value have no location in the file.
+    let line = contents
+        .lines()
+        .nth(source.line().saturating_sub(1))
+        .filter(|_| source.line() > 0)
+        .map(|line| format!(" near `{}`", line.trim()))
+        .unwrap_or_default();
+    let context = record.map_or_else(
+        || format!("at{line}"),
+        |record| format!("in game #{record}{line}"),
+    );
+
+    Error::ParseStats {
+        path: file_path.to_path_buf(),
+        line: source.line(),
+        column: source.column(),
+        context,
+        source,
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("cod_keeper_{name}_{}.json", std::process::id()))
+    }
+
+    #[test]
+    fn test_parse_error_names_game_and_line() -> Result<()> {
+        // Arrange
+        let path = temp_path("malformed");
+        std::fs::write(&path, format!(r#"{{
+  "schema_version": {},
+  "games": [
+    {{ "map": "Rust", "did_win": true, "date_time": "2024-06-28T11:38:46-04:00" }},
+    {{ "map": "Nowhere", "did_win": false, "date_time": "2024-06-28T11:48:46-04:00" }}
+  ]
+}}"#, schema::SCHEMA_VERSION))?;
+
+        // Act
+        let error = JsonStore::open(&path).err();
+        std::fs::remove_file(&path)?;
+
+        // Assert
+        let Some(Error::ParseStats { line, context, .. }) = &error else {
+            panic!("expected a parse error, got {error:?}");
+        };
+        assert_eq!(*line, 5);
+        assert_eq!(context, r#"in game #1 near `{ "map": "Nowhere", "did_win": false, "date_time": "2024-06-28T11:48:46-04:00" }`"#);
+        assert!(error.map(|e| e.to_string()).unwrap_or_default().contains("in game #1 near"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_save_keeps_backup_and_no_temp_file() -> Result<()> {
+        // Arrange
+        let path = temp_path("atomic");
+        let (tmp_path, bak_path) = (with_suffix(&path, ".tmp"), with_suffix(&path, ".bak"));
+        std::fs::write(&path, format!(r#"{{ "schema_version": {}, "games": [] }}"#, schema::SCHEMA_VERSION))?;
+        let mut store = JsonStore::open(&path)?;
+        let game = GamePlayed { did_win: true, ..Default::default() };
+
+        // Act
+        store.append(&game)?;
+        let first = std::fs::read_to_string(&path)?;
+        store.delete(&game)?;
+        let second = std::fs::read_to_string(&path)?;
+        let backup = std::fs::read_to_string(&bak_path)?;
+        let tmp_left = tmp_path.exists();
+        [&path, &bak_path].iter().try_for_each(std::fs::remove_file)?;
+
+        // Assert
+        assert_eq!(backup, first);
+        assert_ne!(second, first);
+        assert!(!tmp_left);
+        Ok(())
+    }
+}
@@ -0,0 +1,189 @@
+use std::collections::HashMap;
+
+use inquire::{Confirm, MultiSelect, Text};
+
+use crate::{
+    error::Error,
+    map::{GunfightMap, MapStats},
+    run::GamePlayed,
+    CodVersion,
+};
+
+/// Wins and losses with one weapon, on one map when `map` is set.
+#[derive(Debug, PartialEq, Eq)]
+pub struct LoadoutStats {
+    pub map: Option<GunfightMap>,
+    pub weapon: String,
+    pub stats: MapStats,
+}
+
+/// Every weapon used in a game of `cod_version`, most used first.
+pub fn catalog(games: &[GamePlayed], cod_version: &CodVersion) -> Vec<String> {
+    let mut uses: HashMap<&str, usize> = HashMap::new();
+    for weapon in games
+        .iter()
+        .filter(|g| GunfightMap::is_in(&g.map, cod_version))
+        .flat_map(|g| &g.weapons)
+    {
+        *uses.entry(weapon).or_default() += 1;
+    }
+
+    let mut catalog = uses.into_iter().collect::<Vec<_>>();
+    catalog.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    catalog
+        .into_iter()
+        .map(|(weapon, _)| weapon.to_string())
+        .collect()
+}
+
+/// Wins and losses per weapon of `games`, best win percentage first.
+///
+/// A game counts for every weapon it was played with.
+pub fn by_weapon(games: &[&GamePlayed]) -> Vec<LoadoutStats> {
+    tally(games, |_| None)
+}
+
+/// Wins and losses per map and weapon of `games`, best win percentage first.
+pub fn by_map_and_weapon(games: &[&GamePlayed]) -> Vec<LoadoutStats> {
+    tally(games, |game| Some(game.map.clone()))
+}
+
+fn tally(
+    games: &[&GamePlayed],
+    map_of: impl Fn(&GamePlayed) -> Option<GunfightMap>,
+) -> Vec<LoadoutStats> {
+    let mut tallies: HashMap<(Option<GunfightMap>, &str), MapStats> = HashMap::new();
+    for game in games {
+        for weapon in &game.weapons {
+            let stats = tallies.entry((map_of(game), weapon)).or_default();
+            if game.did_win {
+                stats.wins += 1;
+            } else {
+                stats.losses += 1;
+            }
+        }
+    }
+
+    let mut loadouts = tallies
+        .into_iter()
+        .map(|((map, weapon), stats)| LoadoutStats {
+            map,
+            weapon: weapon.to_string(),
+            stats,
+        })
+        .collect::<Vec<_>>();
+    loadouts.sort_by(|a, b| {
+        b.stats
+            .get_win_percentage()
+            .total_cmp(&a.stats.get_win_percentage())
+            .then_with(|| (b.stats.wins + b.stats.losses).cmp(&(a.stats.wins + a.stats.losses)))
+            .then_with(|| {
+                a.map
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .cmp(&b.map.as_ref().map(ToString::to_string))
+            })
+            .then_with(|| a.weapon.cmp(&b.weapon))
+    });
+    loadouts
+}
+
+/// Splits a comma separated list of weapons, spelling the ones already in `catalog` the same way.
+pub fn parse_weapons(s: &str, catalog: &[String]) -> Vec<String> {
+    s.split(',')
+        .map(str::trim)
+        .filter(|weapon| !weapon.is_empty())
+        .map(|weapon| {
+            catalog
+                .iter()
+                .find(|known| known.eq_ignore_ascii_case(weapon))
+                .map_or_else(|| weapon.to_string(), Clone::clone)
+        })
+        .collect()
+}
+
+/// Asks which weapons `game` was played with, picking from `catalog` and adding new ones, after
+/// checking there are any to add.
+pub fn prompt_weapons(game: &mut GamePlayed, catalog: &[String]) -> Result<(), Error> {
+    if !Confirm::new("Add weapons?").with_default(false).prompt()? {
+        return Ok(());
+    }
+
+    let mut weapons = if catalog.is_empty() {
+        Vec::new()
+    } else {
+        MultiSelect::new("Weapons used?", catalog.to_vec()).prompt()?
+    };
+    let others = Text::new("Other weapons?")
+        .with_help_message("comma separated, empty to skip")
+        .prompt()?;
+    for weapon in parse_weapons(&others, catalog) {
+        if !weapons.contains(&weapon) {
+            weapons.push(weapon);
+        }
+    }
+    game.weapons = weapons;
+    Ok(())
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    fn game(did_win: bool, map: GunfightMap, weapons: &[&str]) -> GamePlayed {
+        GamePlayed { did_win, map, weapons: weapons.iter().map(ToString::to_string).collect(), ..Default::default() }
+    }
+
+    #[test]
+    fn test_loadout_stats() -> Result<()> {
+        // Arrange
+        let games = [
+            game(true, GunfightMap::Shipment, &["MCW", "Renetti"]),
+            game(false, GunfightMap::Shipment, &["MCW"]),
+            game(true, GunfightMap::Rust, &["MCW"]),
+            game(true, GunfightMap::Rust, &[]),
+        ];
+        let games = games.iter().collect::<Vec<_>>();
+
+        // Act
+        let weapons = by_weapon(&games);
+        let map_weapons = by_map_and_weapon(&games);
+
+        // Assert
+        let summary = |loadouts: &[LoadoutStats]| loadouts.iter().map(|l| (l.map.clone(), l.weapon.clone(), l.stats.wins, l.stats.losses)).collect::<Vec<_>>();
+        assert_eq!(summary(&weapons), vec![
+            (None, "Renetti".to_string(), 1, 0),
+            (None, "MCW".to_string(), 2, 1),
+        ]);
+        assert_eq!(summary(&map_weapons), vec![
+            (Some(GunfightMap::Rust), "MCW".to_string(), 1, 0),
+            (Some(GunfightMap::Shipment), "Renetti".to_string(), 1, 0),
+            (Some(GunfightMap::Shipment), "MCW".to_string(), 1, 1),
+        ]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_catalog_and_parse_weapons() -> Result<()> {
+        // Arrange
+        let games = [
+            game(true, GunfightMap::Shipment, &["MCW", "Renetti"]),
+            game(false, GunfightMap::Meat, &["MCW"]),
+            // Only in MW
+            game(false, GunfightMap::Atrium, &["M4"]),
+        ];
+
+        // Act
+        let catalog = catalog(&games, &CodVersion::MW3);
+
+        // Assert
+        assert_eq!(catalog, vec!["MCW".to_string(), "Renetti".to_string()]);
+        assert_eq!(parse_weapons(" mcw, Holger 556 ,", &catalog), vec!["MCW".to_string(), "Holger 556".to_string()]);
+        Ok(())
+    }
+}
@@ -1,21 +1,51 @@
+use chrono::{DateTime, Local};
+use chrono_tz::Tz;
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use map::GunfightMap;
+use serde::Serialize;
 use std::path::PathBuf;
 use strum_macros::Display;
 use tracing_log::AsTrace;
 
+use crate::combat::CombatLine;
+use crate::day_boundary::DayBoundary;
 use crate::otel::setup_otel;
-use crate::run::run;
+use crate::run::{run, GameSelector};
+use crate::stats::MapSort;
+use crate::tags::TagFilter;
+use crate::trends::Period;
+use crate::window::{DayOrTime, TimeWindow};
 
 const DAY_FMT: &str = "%m-%d-%Y";
 
+pub mod combat;
+pub mod confidence;
+pub mod csv_file;
+pub mod day_boundary;
 pub mod error;
+pub mod form;
 pub mod graph;
+pub mod json_store;
+pub mod loadouts;
 pub mod map;
 pub mod menus;
 pub mod otel;
+pub mod recommend;
+pub mod report;
+pub mod rounds;
 pub mod run;
+pub mod schema;
+pub mod sessions;
+pub mod sqlite_store;
 pub mod stats;
+pub mod store;
+pub mod streaks;
+pub mod tags;
+pub mod teammates;
+pub mod tilt;
+pub mod time_of_day;
+pub mod trends;
+pub mod window;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about)]
@@ -33,32 +63,285 @@ pub struct Cli {
 
 #[derive(Subcommand, Debug, Display)]
 pub enum Commands {
-    Graph,
+    /// Draw a chart of the recorded games.
+    Graph {
+        /// Which chart to draw.
+        #[arg(short, long, value_enum, default_value_t = GraphKind::WinRate)]
+        kind: GraphKind,
+
+        /// File to write the chart to, `.svg` files are drawn as vectors.
+        /// Defaults to a file in `plotters-doc-data` named after the chart.
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Number of games used for the rolling win percentage.
+        #[arg(short, long, default_value_t = 20)]
+        window: usize,
+    },
     Prompt,
+    /// Record a single game without going through the prompts.
+    Add {
+        /// Map the game was played on, e.g. `shipment`.
+        map: GunfightMap,
+
+        /// Whether the game was won or lost.
+        #[arg(value_enum)]
+        result: GameResult,
+
+        /// When the game was played (RFC 3339), defaults to now.
+        #[arg(short, long)]
+        at: Option<DateTime<Local>>,
+
+        /// Final round score as rounds won - rounds lost, e.g. `6-4`.
+        #[arg(short, long, value_parser = rounds::parse_round_score)]
+        rounds: Option<(u8, u8)>,
+
+        /// The game went to overtime.
+        #[arg(long, requires = "rounds")]
+        overtime: bool,
+
+        /// Personal stats as kills/deaths/assists/damage, e.g. `12/8/3/1500`. Assists and damage
+        /// can be left off.
+        #[arg(short, long, value_parser = combat::parse_combat_line)]
+        combat: Option<CombatLine>,
+
+        /// Who the game was played with.
+        #[arg(short, long)]
+        teammate: Option<String>,
+
+        /// Weapon played with, can be given more than once or as a comma separated list.
+        #[arg(short, long = "weapon", value_delimiter = ',')]
+        weapons: Vec<String>,
+
+        /// Tag to find the game by later, e.g. `lag`. Can be given more than once or as a comma
+        /// separated list.
+        #[arg(long = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// Anything else worth remembering about the game.
+        #[arg(short, long)]
+        notes: Option<String>,
+    },
+    /// Remove the most recently played game of the version.
+    Undo,
+    /// Remove a single game.
+    Delete {
+        /// Position of the game among the version's games (starting at 0) or when it was played (RFC
+        /// 3339).
+        target: GameSelector,
+    },
+    /// Change the map, result, time or round score of a single game, prompts when no changes are
+    /// given.
+    Edit {
+        /// Position of the game among the version's games (starting at 0) or when it was played (RFC
+        /// 3339).
+        target: GameSelector,
+
+        /// Map the game was played on.
+        #[arg(short, long)]
+        map: Option<GunfightMap>,
+
+        /// Whether the game was won or lost.
+        #[arg(short, long, value_enum)]
+        result: Option<GameResult>,
+
+        /// When the game was played (RFC 3339).
+        #[arg(short, long)]
+        at: Option<DateTime<Local>>,
+
+        /// Final round score as rounds won - rounds lost, e.g. `6-4`. A changed result swaps the
+        /// recorded score around unless a new one is given.
+        #[arg(long, value_parser = rounds::parse_round_score, conflicts_with = "clear_rounds")]
+        rounds: Option<(u8, u8)>,
+
+        /// The game went to overtime.
+        #[arg(long, requires = "rounds")]
+        overtime: bool,
+
+        /// Forget the round score of the game.
+        #[arg(long)]
+        clear_rounds: bool,
+    },
+    /// Record games from a CSV file with `map`, `result` and `date_time` columns.
+    Import {
+        /// CSV file to read the games from.
+        file: PathBuf,
+    },
+    /// Print the lifetime, today and per map stats.
+    Stats {
+        /// How to print the stats, everything but `table` is meant for other tools to read.
+        #[arg(short, long, value_enum, default_value_t = StatsFormat::Table)]
+        format: StatsFormat,
+
+        /// How to order the maps, `lower-bound` keeps maps with only a few games from the top.
+        #[arg(long, value_enum, default_value_t = MapSort::WinPercentage)]
+        sort_maps: MapSort,
+
+        #[clap(flatten)]
+        window: WindowArgs,
+    },
+    /// Print every play session, games with less than `--gap` minutes between them.
+    Sessions {
+        /// How to print the sessions.
+        #[arg(short, long, value_enum, default_value_t = StatsFormat::Table)]
+        format: StatsFormat,
+
+        /// Minutes without a game after which a new session starts.
+        #[arg(short, long, default_value_t = sessions::DEFAULT_SESSION_GAP_MINUTES, value_parser = clap::value_parser!(i64).range(1..))]
+        gap: i64,
+    },
+    /// Rank maps from the best to the worst to vote for, recent games counting the most.
+    Recommend {
+        /// Maps up for vote, defaults to every map in the selected version.
+        maps: Vec<GunfightMap>,
+
+        /// Days after which a game counts half as much.
+        #[arg(long, default_value_t = recommend::DEFAULT_HALF_LIFE_DAYS, value_parser = clap::value_parser!(u32).range(1..))]
+        half_life_days: u32,
+    },
+    /// Print a row of stats for every week or month games were played in.
+    Trends {
+        /// Length of each row.
+        #[arg(short, long, value_enum, default_value_t = Period::Week)]
+        period: Period,
+    },
+    /// Write the games played in the selected version to a CSV file.
+    Export {
+        /// CSV file to write the games to, prints them when not given.
+        file: Option<PathBuf>,
+    },
 }
 
 #[derive(Args, Debug)]
 pub struct SharedArgs {
+    /// Stat sheet to read and record games in. Files ending in `.db`, `.sqlite` or `.sqlite3`
+    /// are stored in a SQLite database, anything else as JSON.
     #[arg(short, long)]
     stats_path: PathBuf,
 
     #[arg(short, long)]
     cod_version: CodVersion,
+
+    /// Hour of the day (0-23) a new day starts at, so late sessions count as one night.
+    #[arg(long, env = "COD_KEEPER_DAY_START_HOUR", default_value_t = 0, value_parser = clap::value_parser!(u32).range(0..24))]
+    day_start_hour: u32,
+
+    /// Time zone days are counted in, e.g. `America/New_York`. Defaults to the system time zone.
+    #[arg(long, env = "COD_KEEPER_TIMEZONE")]
+    timezone: Option<Tz>,
+
+    /// Only count games with one of these tags in stats, charts and exports.
+    #[arg(long, value_delimiter = ',')]
+    with_tag: Vec<String>,
+
+    /// Leave games with any of these tags out of stats, charts and exports, e.g. `lag`.
+    #[arg(long, value_delimiter = ',')]
+    without_tag: Vec<String>,
 }
 
-#[derive(Debug, ValueEnum, Display, Clone)]
+impl SharedArgs {
+    pub const fn day_boundary(&self) -> DayBoundary {
+        DayBoundary::new(self.day_start_hour, self.timezone)
+    }
+
+    pub fn tag_filter(&self) -> TagFilter {
+        TagFilter::new(&self.with_tag, &self.without_tag)
+    }
+}
+
+/// Narrows the stats down to a span of time, shown next to lifetime and today.
+#[derive(Args, Debug)]
+pub struct WindowArgs {
+    /// Only games from the last N days, counting today.
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..), group = "window")]
+    last_days: Option<u32>,
+
+    /// Only games since Monday.
+    #[arg(long, group = "window")]
+    week: bool,
+
+    /// Only games since the first of the month.
+    #[arg(long, group = "window")]
+    month: bool,
+
+    /// Only games from this season of the selected version, counting from 1.
+    #[arg(long, group = "window")]
+    season: Option<usize>,
+
+    /// Only games played at or after this day (2024-01-31) or time (RFC 3339).
+    #[arg(long, value_parser = window::parse_day_or_time, conflicts_with = "window")]
+    since: Option<DayOrTime>,
+
+    /// Only games played before this day (2024-01-31) or time (RFC 3339).
+    #[arg(long, value_parser = window::parse_day_or_time, conflicts_with = "window")]
+    until: Option<DayOrTime>,
+}
+
+impl WindowArgs {
+    pub fn window(&self) -> Option<TimeWindow> {
+        let range = (self.since.is_some() || self.until.is_some()).then_some(TimeWindow::Range {
+            since: self.since,
+            until: self.until,
+        });
+        self.last_days
+            .map(TimeWindow::LastDays)
+            .or_else(|| self.week.then_some(TimeWindow::Week))
+            .or_else(|| self.month.then_some(TimeWindow::Month))
+            .or_else(|| self.season.map(TimeWindow::Season))
+            .or(range)
+    }
+}
+
+#[derive(Debug, ValueEnum, Display, Clone, PartialEq, Eq, Serialize)]
 pub enum CodVersion {
     MW,
     MW3,
 }
 
+#[derive(Debug, ValueEnum, Display, Clone, Copy, PartialEq, Eq)]
+pub enum GameResult {
+    #[value(alias = "w")]
+    Win,
+    #[value(alias = "l")]
+    Loss,
+}
+
+#[derive(Debug, ValueEnum, Display, Clone, Copy, PartialEq, Eq)]
+pub enum GraphKind {
+    /// Cumulative and rolling win percentage over time.
+    WinRate,
+    /// Wins and losses per map, ordered by win percentage.
+    Maps,
+    /// Win percentage per weekday and hour of the day as a heatmap.
+    TimeOfDay,
+}
+
+#[derive(Debug, ValueEnum, Display, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    Table,
+    Json,
+    Yaml,
+    Csv,
+}
+
+impl GraphKind {
+    pub const fn default_output(self) -> &'static str {
+        match self {
+            Self::WinRate => graph::WIN_RATE_OUT_FILE,
+            Self::Maps => graph::MAP_STATS_OUT_FILE,
+            Self::TimeOfDay => graph::TIME_OF_DAY_OUT_FILE,
+        }
+    }
+}
+
 #[tokio::main]
 async fn main() {
     let cli = Cli::parse();
 
     let guard = setup_otel(cli.verbose.log_level_filter().as_trace());
 
-    if run(cli).is_err() {
+    if let Err(error) = run(cli) {
+        eprintln!("[ERROR]: {error}");
         drop(guard);
         std::process::exit(1);
     }
@@ -1,11 +1,24 @@
 use inquire::Select;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
-use strum_macros::{Display, EnumIter};
+use strum_macros::{Display, EnumIter, EnumString};
 
-use crate::{error::Error, CodVersion};
+use crate::{combat::CombatStats, confidence::WinInterval, error::Error, CodVersion};
 
-#[derive(Serialize, Deserialize, Debug, Clone, EnumIter, Display, PartialEq, Eq, Hash, Default)]
+#[derive(
+    Serialize,
+    Deserialize,
+    Debug,
+    Clone,
+    EnumIter,
+    EnumString,
+    Display,
+    PartialEq,
+    Eq,
+    Hash,
+    Default,
+)]
+#[strum(ascii_case_insensitive)]
 pub enum GunfightMap {
     // Both
     Rust,
@@ -68,12 +81,36 @@ impl GunfightMap {
     pub fn is_mw3(map: &Self) -> bool {
         OPTIONS_MW3.contains(map) || OPTIONS_BOTH.contains(map)
     }
+
+    pub fn is_in(map: &Self, cod_version: &CodVersion) -> bool {
+        match cod_version {
+            CodVersion::MW => Self::is_mw(map),
+            CodVersion::MW3 => Self::is_mw3(map),
+        }
+    }
+
+    /// Every map that can be played in `cod_version`.
+    pub fn playable_in(cod_version: &CodVersion) -> Vec<Self> {
+        Self::iter()
+            .filter(|map| Self::validate(map, cod_version).is_ok())
+            .collect()
+    }
+
+    /// Makes sure `map` is a playable map in `cod_version`.
+    pub fn validate(map: &Self, cod_version: &CodVersion) -> Result<(), Error> {
+        if *map == Self::Back || !Self::is_in(map, cod_version) {
+            return Err(Error::MapNotInVersion(map.clone(), cod_version.clone()));
+        }
+        Ok(())
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Default)]
 pub struct MapStats {
     pub wins: usize,
     pub losses: usize,
+    #[serde(default)]
+    pub combat: CombatStats,
 }
 
 impl MapStats {
@@ -83,16 +120,25 @@ impl MapStats {
         }
         (self.wins as f32 / (self.wins + self.losses) as f32) * 100.0
     }
+
+    pub fn get_win_interval(&self) -> WinInterval {
+        WinInterval::wilson(self.wins, self.losses)
+    }
 }
 
 impl std::fmt::Display for MapStats {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{} - {} ({:.0} %)",
+            "{} - {} ({:.0} %, likely {}",
             self.wins,
             self.losses,
-            self.get_win_percentage()
-        )
+            self.get_win_percentage(),
+            self.get_win_interval(),
+        )?;
+        if self.combat.games > 0 {
+            write!(f, ", {:.2} K/D", self.combat.kd_ratio())?;
+        }
+        write!(f, ")")
     }
 }
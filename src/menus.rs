@@ -4,7 +4,9 @@ use strum_macros::{Display, EnumIter};
 #[derive(Serialize, Deserialize, Debug, EnumIter, Display, PartialEq, Eq, Default)]
 pub enum MainMenuOption {
     EnterGames,
+    Recommend,
     DisplayStats,
+    Undo,
     #[default]
     Back,
 }
@@ -13,7 +15,14 @@ pub enum MainMenuOption {
 pub enum DisplayStatsOption {
     Lifetime,
     CurrentStreak,
+    Streaks,
     Today,
+    TimeWindow,
+    Sessions,
+    Trends,
+    TimeOfDay,
+    Teammates,
+    Loadouts,
     Maps,
     OneMap,
     #[default]
@@ -27,3 +36,16 @@ pub enum DidWinOption {
     #[default]
     Back,
 }
+
+#[derive(Debug, Clone, Copy, EnumIter, Display, PartialEq, Eq)]
+pub enum TimeWindowOption {
+    #[strum(serialize = "Last N Days")]
+    LastDays,
+    #[strum(serialize = "This Week")]
+    Week,
+    #[strum(serialize = "This Month")]
+    Month,
+    Season,
+    #[strum(serialize = "Date Range")]
+    Range,
+}
@@ -25,11 +25,9 @@ fn resource() -> Resource {
     )
 }
 
-pub fn setup_otel(_log_filter: LevelFilter) -> OtelGuard {
-    // TODO: per layer filter
-    // - RUST_LOG for otel.
-    // - clap verbosity flag for logger.
-
+/// Logs go to stderr, so output meant for other tools can be piped from stdout. `RUST_LOG` wins over
+/// `log_filter`, which comes from the verbosity flag.
+pub fn setup_otel(log_filter: LevelFilter) -> OtelGuard {
     let otel_layer = std::env::var("OTEL_COLLECTOR_URL").map_or_else(
         |_| None,
         |url| Some(OpenTelemetryLayer::new(init_tracer(&url))),
@@ -38,14 +36,12 @@ pub fn setup_otel(_log_filter: LevelFilter) -> OtelGuard {
     let filter = if std::env::var("RUST_LOG").is_ok() {
         EnvFilter::builder().from_env_lossy()
     } else {
-        "warn,cod_keeper=debug"
-            .parse()
-            .expect("valid EnvFilter value can be parsed")
+        EnvFilter::default().add_directive(log_filter.into())
     };
 
     tracing_subscriber::registry()
         .with(filter) // Read global subscriber filter from `RUST_LOG`
-        .with(tracing_subscriber::fmt::layer()) // Setup logging layer
+        .with(tracing_subscriber::fmt::layer().with_writer(std::io::stderr)) // Setup logging layer
         .with(otel_layer)
         .init();
 
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Local};
+use strum_macros::Display;
+
+use crate::{map::GunfightMap, run::GamePlayed, stats::StatsGroup};
+
+/// Days after which a game counts half as much towards a recommendation.
+pub const DEFAULT_HALF_LIFE_DAYS: u32 = 30;
+
+/// Number of most recent games on a map shown as its form.
+const RECENT_FORM_GAMES: usize = 5;
+
+/// Made up games every map starts with, half of them wins, so a map with one or two games stays
+/// close to even instead of jumping to 0 or 100 %.
+const PRIOR_GAMES: f64 = 4.0;
+
+/// Scores at or above this are worth voting for, at or below `100 - VOTE_SCORE` worth avoiding.
+const VOTE_SCORE: f32 = 55.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum Verdict {
+    Vote,
+    Neutral,
+    Avoid,
+    #[strum(serialize = "No Data")]
+    NoData,
+}
+
+/// How a map has gone for us, and whether to vote for it.
+#[derive(Debug, PartialEq)]
+pub struct MapRecommendation {
+    pub map: GunfightMap,
+    /// Win percentage with recent games counting more, pulled towards 50 % for small samples.
+    pub score: f32,
+    pub wins: usize,
+    pub losses: usize,
+    /// Results of the last games on the map, oldest first.
+    pub recent_form: Vec<bool>,
+    pub verdict: Verdict,
+}
+
+impl MapRecommendation {
+    pub const fn games(&self) -> usize {
+        self.wins + self.losses
+    }
+
+    /// Recent form as `W` and `L`, e.g. `WWLW`.
+    pub fn recent_form_string(&self) -> String {
+        self.recent_form
+            .iter()
+            .map(|did_win| if *did_win { 'W' } else { 'L' })
+            .collect()
+    }
+}
+
+/// Ranks `maps` from the best to the worst pick based on `games`, ordered oldest first.
+pub fn recommend_maps(
+    games: &[&GamePlayed],
+    maps: &[GunfightMap],
+    now: DateTime<Local>,
+    half_life_days: u32,
+) -> Vec<MapRecommendation> {
+    let stats = StatsGroup::from_games(games.iter().copied());
+    let mut recommendations = maps
+        .iter()
+        .map(|map| {
+            let on_map = games.iter().filter(|g| g.map == *map).collect::<Vec<_>>();
+            let (weighted_wins, weighted_games) =
+                on_map.iter().fold((0.0, 0.0), |(wins, total), game| {
+                    let age_days = (now - game.date_time).num_seconds().max(0) as f64 / 86_400.0;
+                    let weight = 0.5_f64.powf(age_days / f64::from(half_life_days.max(1)));
+                    (
+                        wins + if game.did_win { weight } else { 0.0 },
+                        total + weight,
+                    )
+                });
+            let score = ((weighted_wins + PRIOR_GAMES / 2.0) / (weighted_games + PRIOR_GAMES)
+                * 100.0) as f32;
+            let map_stats = stats.get_map_stats(map);
+
+            MapRecommendation {
+                map: map.clone(),
+                score,
+                wins: map_stats.map_or(0, |m| m.wins),
+                losses: map_stats.map_or(0, |m| m.losses),
+                recent_form: on_map
+                    .iter()
+                    .skip(on_map.len().saturating_sub(RECENT_FORM_GAMES))
+                    .map(|g| g.did_win)
+                    .collect(),
+                verdict: if on_map.is_empty() {
+                    Verdict::NoData
+                } else if score >= VOTE_SCORE {
+                    Verdict::Vote
+                } else if score <= 100.0 - VOTE_SCORE {
+                    Verdict::Avoid
+                } else {
+                    Verdict::Neutral
+                },
+            }
+        })
+        .collect::<Vec<_>>();
+
+    recommendations.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then_with(|| b.games().cmp(&a.games()))
+            .then_with(|| a.map.to_string().cmp(&b.map.to_string()))
+    });
+    recommendations
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Duration, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_recent_games_count_more() -> Result<()> {
+        // Arrange
+        let now = Local.with_ymd_and_hms(2024, 3, 1, 20, 0, 0).unwrap();
+        let days_ago = |days| now - Duration::days(days);
+        let games = [
+            // Lost a lot on Rust a long time ago, won lately.
+            GamePlayed { did_win: false, map: GunfightMap::Rust, date_time: days_ago(200), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Rust, date_time: days_ago(200), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Rust, date_time: days_ago(199), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Rust, date_time: days_ago(2), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Rust, date_time: days_ago(1), ..Default::default() },
+            // The other way around on Meat.
+            GamePlayed { did_win: true, map: GunfightMap::Meat, date_time: days_ago(200), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Meat, date_time: days_ago(199), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Meat, date_time: days_ago(2), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Meat, date_time: days_ago(1), ..Default::default() },
+        ];
+        let mut games = games.iter().collect::<Vec<_>>();
+        games.sort_by_key(|g| g.date_time);
+
+        // Act
+        let recommendations = recommend_maps(&games, &[GunfightMap::Meat, GunfightMap::Shipment, GunfightMap::Rust], now, DEFAULT_HALF_LIFE_DAYS);
+
+        // Assert
+        assert_eq!(
+            recommendations.iter().map(|r| (r.map.clone(), r.verdict)).collect::<Vec<_>>(),
+            vec![(GunfightMap::Rust, Verdict::Vote), (GunfightMap::Shipment, Verdict::NoData), (GunfightMap::Meat, Verdict::Avoid)],
+        );
+        assert_eq!(recommendations[0].recent_form_string(), "LLLWW");
+        assert_eq!((recommendations[0].wins, recommendations[0].losses), (2, 3));
+        Ok(())
+    }
+}
@@ -0,0 +1,301 @@
+use std::io::Write;
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+
+use crate::{
+    combat::CombatStats,
+    confidence::WinInterval,
+    error::Error,
+    map::{GunfightMap, MapStats},
+    rounds::RoundStats,
+    stats::{MapSort, Stats, StatsGroup},
+    store::GameQuery,
+    teammates::TeammateStats,
+    window::TimeWindow,
+    CodVersion,
+};
+
+/// A [`Stats`] laid out for other tools to read.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct StatsReport {
+    pub cod_version: CodVersion,
+    pub lifetime: GroupReport,
+    pub today: GroupReport,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<WindowReport>,
+    /// Lifetime stats per teammate, most games together first.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub teammates: Vec<TeammateReport>,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct WindowReport {
+    pub name: String,
+    pub since: Option<DateTime<Local>>,
+    pub until: Option<DateTime<Local>>,
+    #[serde(flatten)]
+    pub stats: GroupReport,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct TeammateReport {
+    pub teammate: String,
+    pub best_maps: Vec<GunfightMap>,
+    #[serde(flatten)]
+    pub stats: GroupReport,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct GroupReport {
+    pub wins: usize,
+    pub losses: usize,
+    pub win_percentage: f32,
+    pub win_interval: WinInterval,
+    pub current_streak: StreakReport,
+    pub high_win_streak: usize,
+    pub high_loss_streak: usize,
+    /// Left out when no game was recorded with a round score.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rounds: Option<RoundStats>,
+    /// Left out when no game was recorded with kills and deaths.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combat: Option<CombatStats>,
+    /// Ordered best first by the [`MapSort`] the report was made with.
+    pub maps: Vec<MapReport>,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq)]
+pub struct StreakReport {
+    pub result: StreakResult,
+    pub length: usize,
+}
+
+#[derive(Serialize, Debug, PartialEq, Eq, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum StreakResult {
+    Win,
+    Loss,
+}
+
+#[derive(Serialize, Debug, PartialEq)]
+pub struct MapReport {
+    pub map: GunfightMap,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_percentage: f32,
+    pub win_interval: WinInterval,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub combat: Option<CombatStats>,
+}
+
+/// One line of the CSV report, the totals of a group have no `map`.
+#[derive(Serialize, Debug, PartialEq)]
+struct CsvRow<'a> {
+    group: &'a str,
+    map: Option<&'a GunfightMap>,
+    wins: usize,
+    losses: usize,
+    win_percentage: f32,
+    win_lower: f32,
+    win_upper: f32,
+    streak_result: Option<StreakResult>,
+    streak_length: Option<usize>,
+    high_win_streak: Option<usize>,
+    high_loss_streak: Option<usize>,
+}
+
+impl StatsReport {
+    pub fn new(stats: &Stats, cod_version: &CodVersion, map_sort: MapSort) -> Self {
+        Self {
+            cod_version: cod_version.clone(),
+            lifetime: GroupReport::new(&stats.lifet, map_sort),
+            today: GroupReport::new(&stats.today, map_sort),
+            window: None,
+            teammates: Vec::new(),
+        }
+    }
+
+    /// Adds the stats of the games in `window`, which `query` was built from.
+    pub fn with_window(
+        mut self,
+        window: &TimeWindow,
+        query: &GameQuery,
+        stats: &StatsGroup,
+        map_sort: MapSort,
+    ) -> Self {
+        self.window = Some(WindowReport {
+            name: window.to_string(),
+            since: query.since,
+            until: query.until,
+            stats: GroupReport::new(stats, map_sort),
+        });
+        self
+    }
+
+    /// Adds the stats of the games played with each of `teammates`.
+    pub fn with_teammates(mut self, teammates: &[TeammateStats], map_sort: MapSort) -> Self {
+        self.teammates = teammates
+            .iter()
+            .map(|teammate| TeammateReport {
+                teammate: teammate.teammate.clone(),
+                best_maps: teammate.best_maps().into_iter().cloned().collect(),
+                stats: GroupReport::new(&teammate.stats, map_sort),
+            })
+            .collect();
+        self
+    }
+
+    pub fn write_json(&self, mut writer: impl Write) -> Result<(), Error> {
+        serde_json::to_writer_pretty(&mut writer, self).map_err(Error::SerializeStats)?;
+        writeln!(writer).map_err(|e| Error::SerializeStats(serde_json::Error::io(e)))
+    }
+
+    pub fn write_yaml(&self, writer: impl Write) -> Result<(), Error> {
+        Ok(serde_yaml::to_writer(writer, self)?)
+    }
+
+    /// Writes a row with the totals of each group followed by a row per map.
+    pub fn write_csv(&self, writer: impl Write) -> Result<(), Error> {
+        let mut writer = csv::Writer::from_writer(writer);
+        let mut groups = vec![("lifetime", &self.lifetime), ("today", &self.today)];
+        if let Some(window) = &self.window {
+            groups.push((&window.name, &window.stats));
+        }
+        for (name, group) in groups {
+            writer.serialize(CsvRow {
+                group: name,
+                map: None,
+                wins: group.wins,
+                losses: group.losses,
+                win_percentage: group.win_percentage,
+                win_lower: group.win_interval.lower,
+                win_upper: group.win_interval.upper,
+                streak_result: Some(group.current_streak.result),
+                streak_length: Some(group.current_streak.length),
+                high_win_streak: Some(group.high_win_streak),
+                high_loss_streak: Some(group.high_loss_streak),
+            })?;
+            for map in &group.maps {
+                writer.serialize(CsvRow {
+                    group: name,
+                    map: Some(&map.map),
+                    wins: map.wins,
+                    losses: map.losses,
+                    win_percentage: map.win_percentage,
+                    win_lower: map.win_interval.lower,
+                    win_upper: map.win_interval.upper,
+                    streak_result: None,
+                    streak_length: None,
+                    high_win_streak: None,
+                    high_loss_streak: None,
+                })?;
+            }
+        }
+        writer.flush().map_err(csv::Error::from)?;
+        Ok(())
+    }
+}
+
+impl GroupReport {
+    fn new(stats: &StatsGroup, map_sort: MapSort) -> Self {
+        Self {
+            wins: stats.wins,
+            losses: stats.losses,
+            win_percentage: stats.get_win_percentage(),
+            win_interval: stats.get_win_interval(),
+            current_streak: if stats.last_was_win {
+                StreakReport {
+                    result: StreakResult::Win,
+                    length: stats.win_streak,
+                }
+            } else {
+                StreakReport {
+                    result: StreakResult::Loss,
+                    length: stats.loss_streak,
+                }
+            },
+            high_win_streak: stats.high_win_streak,
+            high_loss_streak: stats.high_loss_streak,
+            rounds: Some(stats.rounds).filter(|rounds| rounds.games > 0),
+            combat: Some(stats.combat).filter(|combat| combat.games > 0),
+            maps: stats
+                .get_map_stats_sorted_by(map_sort)
+                .into_iter()
+                .map(|(map, map_stats)| MapReport::new(map, map_stats))
+                .collect(),
+        }
+    }
+}
+
+impl MapReport {
+    fn new(map: &GunfightMap, stats: &MapStats) -> Self {
+        Self {
+            map: map.clone(),
+            wins: stats.wins,
+            losses: stats.losses,
+            win_percentage: stats.get_win_percentage(),
+            win_interval: stats.get_win_interval(),
+            combat: Some(stats.combat).filter(|combat| combat.games > 0),
+        }
+    }
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use crate::{day_boundary::DayBoundary, run::GamePlayed};
+
+    use super::*;
+
+    fn stats() -> Result<Stats> {
+        let games = vec![
+            GamePlayed { did_win: true, map: GunfightMap::Shipment, date_time: Local.with_ymd_and_hms(2023, 12, 1, 20, 0, 0).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Rust, date_time: Local.with_ymd_and_hms(2023, 12, 2, 20, 0, 0).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Rust, date_time: Local.with_ymd_and_hms(2023, 12, 2, 20, 10, 0).unwrap(), ..Default::default() },
+        ];
+        Ok(Stats::new(&games, Local.with_ymd_and_hms(2023, 12, 2, 23, 0, 0).unwrap(), &CodVersion::MW3, &DayBoundary::default())?)
+    }
+
+    #[test]
+    fn test_group_report() -> Result<()> {
+        // Arrange
+        let stats = stats()?;
+
+        // Act
+        let report = StatsReport::new(&stats, &CodVersion::MW3, MapSort::WinPercentage);
+
+        // Assert
+        assert_eq!(report.lifetime.current_streak, StreakReport { result: StreakResult::Loss, length: 2 });
+        assert_eq!(report.lifetime.maps.iter().map(|m| m.map.clone()).collect::<Vec<_>>(), vec![GunfightMap::Shipment, GunfightMap::Rust]);
+        assert_eq!(report.today.wins, 0);
+        assert_eq!(report.today.losses, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_write_csv() -> Result<()> {
+        // Arrange
+        let report = StatsReport::new(&stats()?, &CodVersion::MW3, MapSort::LowerBound);
+        let mut out = Vec::new();
+
+        // Act
+        report.write_csv(&mut out)?;
+
+        // Assert
+        assert_eq!(String::from_utf8(out)?, "\
+            group,map,wins,losses,win_percentage,win_lower,win_upper,streak_result,streak_length,high_win_streak,high_loss_streak\n\
+            lifetime,,1,2,33.333336,6.1490316,79.234505,loss,2,1,2\n\
+            lifetime,Shipment,1,0,100.0,20.65433,100.0,,,,\n\
+            lifetime,Rust,0,2,0.0,0.0,65.7628,,,,\n\
+            today,,0,2,0.0,0.0,65.7628,loss,2,0,2\n\
+            today,Rust,0,2,0.0,0.0,65.7628,,,,\n\
+        ");
+        Ok(())
+    }
+}
@@ -0,0 +1,184 @@
+use inquire::{validator::Validation, Confirm, Text};
+use serde::Serialize;
+
+use crate::{error::Error, run::GamePlayed};
+
+/// Rounds it takes to win a game of Gunfight.
+pub const ROUNDS_TO_WIN: u8 = 6;
+
+/// Round scores of the games recorded with one.
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct RoundStats {
+    /// Games recorded with a round score.
+    pub games: usize,
+    pub rounds_won: usize,
+    pub rounds_lost: usize,
+    /// Games decided by a single round, e.g. 6 - 5.
+    pub close_wins: usize,
+    pub close_losses: usize,
+    pub overtime_wins: usize,
+    pub overtime_losses: usize,
+}
+
+impl RoundStats {
+    /// Counts the round score of `game`, games without one are skipped.
+    pub fn add(&mut self, game: &GamePlayed) {
+        let Some((won, lost)) = game.round_score() else {
+            return;
+        };
+        self.games += 1;
+        self.rounds_won += usize::from(won);
+        self.rounds_lost += usize::from(lost);
+        if won.abs_diff(lost) == 1 {
+            if game.did_win {
+                self.close_wins += 1;
+            } else {
+                self.close_losses += 1;
+            }
+        }
+        if game.overtime {
+            if game.did_win {
+                self.overtime_wins += 1;
+            } else {
+                self.overtime_losses += 1;
+            }
+        }
+    }
+
+    /// Rounds won minus rounds lost.
+    pub const fn differential(&self) -> i64 {
+        self.rounds_won as i64 - self.rounds_lost as i64
+    }
+
+    /// Round differential per game recorded with a score.
+    pub fn average_differential(&self) -> f32 {
+        if self.games == 0 {
+            return 0.0;
+        }
+        self.differential() as f32 / self.games as f32
+    }
+}
+
+/// Parses a round score written as `won-lost`, e.g. `6-4`.
+pub fn parse_round_score(s: &str) -> Result<(u8, u8), String> {
+    let (won, lost) = s
+        .split_once('-')
+        .ok_or_else(|| format!("[{s}] is not a round score like 6-4"))?;
+    let parse = |rounds: &str| {
+        rounds
+            .trim()
+            .parse::<u8>()
+            .map_err(|_| format!("[{rounds}] is not a number of rounds"))
+    };
+    Ok((parse(won)?, parse(lost)?))
+}
+
+/// Checks that a round score of `won` - `lost` can end in a win or loss as given by `did_win`, and
+/// that only a game decided in the last round went to overtime.
+pub const fn validate_round_score(
+    did_win: bool,
+    won: u8,
+    lost: u8,
+    overtime: bool,
+) -> Result<(), Error> {
+    let (winner, loser) = if did_win { (won, lost) } else { (lost, won) };
+    if winner != ROUNDS_TO_WIN || loser >= ROUNDS_TO_WIN {
+        return Err(Error::InvalidRoundScore { won, lost, did_win });
+    }
+    if overtime && !went_the_distance(won, lost) {
+        return Err(Error::InvalidOvertime { won, lost });
+    }
+    Ok(())
+}
+
+/// Whether the game was decided in the last possible round, the only way to reach overtime.
+pub const fn went_the_distance(won: u8, lost: u8) -> bool {
+    won.abs_diff(lost) == 1 && (won == ROUNDS_TO_WIN || lost == ROUNDS_TO_WIN)
+}
+
+/// Asks for the round score of `game`, which is left without one when the answer is empty.
+pub fn prompt_round_score(game: &mut GamePlayed) -> Result<(), Error> {
+    let did_win = game.did_win;
+    let answer = Text::new("Round score?")
+        .with_help_message("rounds won - rounds lost like 6-4, empty to skip")
+        .with_validator(move |s: &str| {
+            Ok(match s.trim() {
+                "" => Validation::Valid,
+                s => parse_round_score(s)
+                    .and_then(|(won, lost)| {
+                        validate_round_score(did_win, won, lost, false).map_err(|e| e.to_string())
+                    })
+                    .map_or_else(|e| Validation::Invalid(e.into()), |()| Validation::Valid),
+            })
+        })
+        .prompt()?;
+    let Some((won, lost)) = Some(answer.trim())
+        .filter(|s| !s.is_empty())
+        .and_then(|s| parse_round_score(s).ok())
+    else {
+        return Ok(());
+    };
+
+    game.rounds_won = Some(won);
+    game.rounds_lost = Some(lost);
+    game.overtime = went_the_distance(won, lost)
+        && Confirm::new("Went to overtime?")
+            .with_default(false)
+            .prompt()?;
+    Ok(())
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::map::GunfightMap;
+
+    #[test]
+    fn test_round_stats() -> Result<()> {
+        // Arrange
+        let games = [
+            GamePlayed { did_win: true, map: GunfightMap::Shipment, rounds_won: Some(6), rounds_lost: Some(5), overtime: true, ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Rust, rounds_won: Some(0), rounds_lost: Some(6), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Rust, rounds_won: Some(5), rounds_lost: Some(6), ..Default::default() },
+            // Recorded without a score
+            GamePlayed { did_win: true, map: GunfightMap::Meat, ..Default::default() },
+        ];
+
+        // Act
+        let mut stats = RoundStats::default();
+        games.iter().for_each(|game| stats.add(game));
+
+        // Assert
+        assert_eq!(stats, RoundStats { games: 3, rounds_won: 11, rounds_lost: 17, close_wins: 1, close_losses: 1, overtime_wins: 1, overtime_losses: 0 });
+        assert_eq!(stats.differential(), -6);
+        assert_eq!(stats.average_differential(), -2.0);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_and_validate_round_score() -> Result<()> {
+        // Act / Assert
+        assert_eq!(parse_round_score("6-4"), Ok((6, 4)));
+        assert_eq!(parse_round_score(" 3 - 6 "), Ok((3, 6)));
+        assert!(parse_round_score("six-four").is_err());
+        assert!(validate_round_score(true, 6, 4, false).is_ok());
+        assert!(validate_round_score(false, 3, 6, false).is_ok());
+        assert!(validate_round_score(true, 6, 5, true).is_ok());
+        assert!(validate_round_score(false, 5, 6, true).is_ok());
+        assert!(validate_round_score(true, 3, 6, false).is_err());
+        assert!(validate_round_score(false, 6, 7, false).is_err());
+        // The winner needs all six rounds
+        assert!(validate_round_score(true, 3, 2, false).is_err());
+        assert!(validate_round_score(true, 6, 6, false).is_err());
+        assert!(validate_round_score(false, 6, 6, false).is_err());
+        // Only a 6 - 5 goes to overtime
+        assert!(validate_round_score(false, 0, 6, true).is_err());
+        assert!(validate_round_score(true, 6, 4, true).is_err());
+        Ok(())
+    }
+}
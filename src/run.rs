@@ -1,7 +1,11 @@
-use std::{fs::File, path::Path};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
 
-use chrono::Local;
-use inquire::Select;
+use chrono::{Duration, Local};
+use inquire::{Confirm, MultiSelect, Select};
 use tracing::instrument;
 
 use anyhow::Result;
@@ -15,74 +19,572 @@ use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 
 use crate::{
+    combat::prompt_combat,
+    csv_file,
+    day_boundary::DayBoundary,
     error::Error,
-    map::GunfightMap,
+    form::Form,
+    graph,
+    loadouts::{
+        by_map_and_weapon, by_weapon, catalog, parse_weapons, prompt_weapons, LoadoutStats,
+    },
+    map::{GunfightMap, MapStats},
     menus::{DidWinOption, DisplayStatsOption, MainMenuOption},
-    stats::{Stats, StatsGroup},
-    Cli, CodVersion, Commands, DAY_FMT,
+    recommend::{recommend_maps, MapRecommendation, Verdict, DEFAULT_HALF_LIFE_DAYS},
+    report::StatsReport,
+    rounds::{prompt_round_score, validate_round_score},
+    sessions::{find_sessions, Session, DEFAULT_SESSION_GAP_MINUTES},
+    stats::{filter_by_version, MapSort, Stats, StatsGroup},
+    store::{open_store, GameQuery, GameStore},
+    streaks::StreakDistribution,
+    tags::{known_tags, parse_tags, prompt_notes_and_tags, TagFilter},
+    teammates::{prompt_teammate, roster, teammate_stats, TeammateStats},
+    tilt::{check_tilt, TiltReason},
+    time_of_day::TimeOfDayStats,
+    trends::{group_by_period, Period, PeriodStats},
+    window::TimeWindow,
+    Cli, CodVersion, Commands, GameResult, GraphKind, StatsFormat,
 };
 
 #[instrument(skip(cli))]
 pub fn run(cli: Cli) -> Result<(), Error> {
+    let cod_version = &cli.args.cod_version;
+    let day_boundary = &cli.args.day_boundary();
+    let tag_filter = &cli.args.tag_filter();
     let file_path = cli.args.stats_path;
-    tracing::info!(stats_path=?file_path, "checking if file exists");
-
-    if !file_path.exists() {
-        tracing::error!(stats_path=?file_path, "file does not exist");
-        return Err(Error::StatsFileNotFound(file_path));
-    }
-
-    let mut games = load(&file_path);
+    // Importing is how a new database gets its first games, anything else needs an existing one.
+    let create = matches!(cli.command, Commands::Import { .. });
+    let mut store = open_store(&file_path, create)?;
+    let store = store.as_mut();
+    let mut games = store.load_all()?;
 
     let result = match cli.command {
-        Commands::Prompt => run_main_menu(&file_path, &mut games, &cli.args.cod_version),
-        Commands::Graph => {
-            // graph::draw_graph(games).unwrap();
-            todo!();
+        Commands::Prompt => run_main_menu(
+            &file_path,
+            store,
+            &mut games,
+            cod_version,
+            day_boundary,
+            tag_filter,
+        ),
+        Commands::Graph {
+            kind,
+            output,
+            window,
+        } => run_graph(
+            &tag_filter.apply(&games),
+            cod_version,
+            day_boundary,
+            kind,
+            output,
+            window,
+        ),
+        Commands::Add {
+            map,
+            result,
+            at,
+            rounds,
+            overtime,
+            combat,
+            teammate,
+            weapons,
+            tags,
+            notes,
+        } => {
+            let game = GamePlayed {
+                map,
+                did_win: result == GameResult::Win,
+                date_time: at.unwrap_or_else(Local::now),
+                rounds_won: rounds.map(|(won, _)| won),
+                rounds_lost: rounds.map(|(_, lost)| lost),
+                overtime,
+                kills: combat.map(|c| c.kills),
+                deaths: combat.map(|c| c.deaths),
+                assists: combat.and_then(|c| c.assists),
+                damage: combat.and_then(|c| c.damage),
+                teammate,
+                weapons: parse_weapons(&weapons.join(","), &catalog(&games, cod_version)),
+                tags: parse_tags(&tags.join(",")),
+                notes,
+            };
+            run_add(
+                store,
+                &mut games,
+                cod_version,
+                day_boundary,
+                tag_filter,
+                game,
+            )
+        }
+        Commands::Undo => run_delete(
+            store,
+            &mut games,
+            cod_version,
+            day_boundary,
+            tag_filter,
+            None,
+        ),
+        Commands::Delete { target } => run_delete(
+            store,
+            &mut games,
+            cod_version,
+            day_boundary,
+            tag_filter,
+            Some(&target),
+        ),
+        Commands::Edit {
+            target,
+            map,
+            result,
+            at,
+            rounds,
+            overtime,
+            clear_rounds,
+        } => run_edit(
+            store,
+            &mut games,
+            cod_version,
+            day_boundary,
+            tag_filter,
+            &target,
+            GameEdit {
+                map,
+                result,
+                at,
+                rounds,
+                overtime,
+                clear_rounds,
+            },
+        ),
+        Commands::Import { file } => run_import(store, &mut games, cod_version, &file),
+        Commands::Stats {
+            format,
+            sort_maps,
+            window,
+        } => window
+            .window()
+            .map(|window| window_stats(store, window, cod_version, day_boundary, tag_filter))
+            .transpose()
+            .and_then(|window| {
+                run_stats(
+                    &tag_filter.apply(&games),
+                    cod_version,
+                    day_boundary,
+                    format,
+                    sort_maps,
+                    window,
+                )
+            }),
+        Commands::Sessions { format, gap } => {
+            run_sessions(&tag_filter.apply(&games), cod_version, format, gap)
+        }
+        Commands::Recommend {
+            maps,
+            half_life_days,
+        } => run_recommend(&tag_filter.apply(&games), cod_version, maps, half_life_days),
+        Commands::Trends { period } => {
+            display_trends(&group_by_period(
+                &filter_by_version(&tag_filter.apply(&games), cod_version),
+                period,
+                day_boundary,
+            ));
+            Ok(())
+        }
+        Commands::Export { file } => {
+            run_export(&tag_filter.apply(&games), cod_version, file.as_deref())
         }
     };
 
+    // Every change is written to the store as soon as it is made, there is nothing left to save.
     if let Err(error) = &result {
-        tracing::error!("Encountered error, saving and exiting [{error}]");
+        tracing::error!("Encountered error, exiting [{error}]");
     };
 
-    save(&mut games, &file_path);
-
     result
 }
 
 #[instrument(skip(games))]
-fn save(games: &mut Vec<GamePlayed>, file_path: &Path) {
-    tracing::debug!("sorting games");
-    games.sort_by(|a, b| a.date_time.cmp(&b.date_time));
-    tracing::trace!(stats_path=?file_path, "writing to file");
-    serde_json::to_writer_pretty(
-        File::create(file_path).expect("stats file should be able to be created"),
-        &games,
-    )
-    .expect("stats type should be able to be serialized into a writer");
-    tracing::trace!(stats_path=?file_path, "wrote to file");
+fn run_graph(
+    games: &[GamePlayed],
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    kind: GraphKind,
+    output: Option<PathBuf>,
+    window: usize,
+) -> Result<(), Error> {
+    let output = output.unwrap_or_else(|| PathBuf::from(kind.default_output()));
+    match kind {
+        GraphKind::WinRate => graph::draw_win_rate_graph(games, cod_version, &output, window),
+        GraphKind::Maps => {
+            let stats = Stats::new(games, Local::now(), cod_version, day_boundary)?;
+            graph::draw_map_graph(&stats.lifet, cod_version, &output)
+        }
+        GraphKind::TimeOfDay => graph::draw_time_of_day_graph(
+            &TimeOfDayStats::new(&filter_by_version(games, cod_version), day_boundary),
+            cod_version,
+            &output,
+        ),
+    }
 }
 
-#[instrument]
-fn load(file_path: &Path) -> Vec<GamePlayed> {
-    tracing::trace!(stats_path=?file_path, "loading file data");
-    let mut games: Vec<GamePlayed> = serde_json::from_str(
-        &std::fs::read_to_string(file_path).expect("stats file should be able to be read"),
-    )
-    .expect("stats file should be able to be deserialized into type");
-    games.sort_by(|a, b| a.date_time.cmp(&b.date_time));
-    tracing::trace!(stats_path=?file_path, "file data loaded");
+#[instrument(skip(store, games))]
+fn run_add(
+    store: &mut dyn GameStore,
+    games: &mut Vec<GamePlayed>,
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    tag_filter: &TagFilter,
+    game: GamePlayed,
+) -> Result<(), Error> {
+    GunfightMap::validate(&game.map, cod_version)?;
+    if let Some((won, lost)) = game.round_score() {
+        validate_round_score(game.did_win, won, lost, game.overtime)?;
+    }
+
+    store.append(&game)?;
+    games.push(game.clone());
+    games.sort_by_key(|g| g.date_time);
+
+    tracing::info!(game.map=%game.map, game.did_win=game.did_win, "recorded game");
+
+    let stats = Stats::new(
+        &tag_filter.apply(games),
+        Local::now(),
+        cod_version,
+        day_boundary,
+    )?;
+    print_game_saved(&game, &stats);
+    Ok(())
+}
+
+/// Position of the most recent game of `cod_version`.
+fn last_in_version(games: &[GamePlayed], cod_version: &CodVersion) -> Option<usize> {
     games
+        .iter()
+        .rposition(|g| GunfightMap::is_in(&g.map, cod_version))
+}
+
+/// Removes the game matching `target`, or the most recent game when there is no target.
+#[instrument(skip(store, games))]
+fn run_delete(
+    store: &mut dyn GameStore,
+    games: &mut Vec<GamePlayed>,
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    tag_filter: &TagFilter,
+    target: Option<&GameSelector>,
+) -> Result<(), Error> {
+    let index = match target {
+        Some(target) => target.position(games, cod_version)?,
+        None => last_in_version(games, cod_version).ok_or(Error::NoGamesRecorded)?,
+    };
+    if !store.delete(&games[index])? {
+        return Err(Error::GameNotFound(games[index].to_string()));
+    }
+    let game = games.remove(index);
+
+    tracing::info!(game.map=%game.map, game.did_win=game.did_win, "removed game");
+    println!("Removed {game}.");
+
+    let stats = Stats::new(
+        &tag_filter.apply(games),
+        Local::now(),
+        cod_version,
+        day_boundary,
+    )?;
+    println!("{}.", streak_message(&stats.lifet));
+    Ok(())
+}
+
+#[derive(Debug, Default)]
+pub struct GameEdit {
+    pub map: Option<GunfightMap>,
+    pub result: Option<GameResult>,
+    pub at: Option<DateTime<Local>>,
+    pub rounds: Option<(u8, u8)>,
+    pub overtime: bool,
+    pub clear_rounds: bool,
+}
+
+impl GameEdit {
+    const fn is_empty(&self) -> bool {
+        self.map.is_none()
+            && self.result.is_none()
+            && self.at.is_none()
+            && self.rounds.is_none()
+            && !self.clear_rounds
+    }
+}
+
+/// Changes the game matching `target`, prompting for the map and result when nothing was given.
+#[instrument(skip(store, games))]
+fn run_edit(
+    store: &mut dyn GameStore,
+    games: &mut [GamePlayed],
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    tag_filter: &TagFilter,
+    target: &GameSelector,
+    edit: GameEdit,
+) -> Result<(), Error> {
+    let index = target.position(games, cod_version)?;
+    let mut game = games[index].clone();
+    println!("Editing {game}.");
+
+    if edit.is_empty() {
+        match GunfightMap::get_map_choice(cod_version)? {
+            GunfightMap::Back => return Ok(()),
+            map => game.map = map,
+        }
+        match Select::new("Did you win?", DidWinOption::iter().collect()).prompt()? {
+            DidWinOption::Yes => game.did_win = true,
+            DidWinOption::No => game.did_win = false,
+            DidWinOption::Back => return Ok(()),
+        }
+        game.rounds_won = None;
+        game.rounds_lost = None;
+        game.overtime = false;
+        prompt_round_score(&mut game)?;
+    } else {
+        if let Some(map) = edit.map {
+            game.map = map;
+        }
+        if let Some(result) = edit.result {
+            let did_win = result == GameResult::Win;
+            if did_win != game.did_win {
+                // The score was kept from our side, a flipped result flips it too.
+                std::mem::swap(&mut game.rounds_won, &mut game.rounds_lost);
+            }
+            game.did_win = did_win;
+        }
+        if let Some(at) = edit.at {
+            game.date_time = at;
+        }
+        if let Some((won, lost)) = edit.rounds {
+            game.rounds_won = Some(won);
+            game.rounds_lost = Some(lost);
+            game.overtime = edit.overtime;
+        }
+        if edit.clear_rounds {
+            game.rounds_won = None;
+            game.rounds_lost = None;
+            game.overtime = false;
+        }
+    }
+    GunfightMap::validate(&game.map, cod_version)?;
+    if let Some((won, lost)) = game.round_score() {
+        validate_round_score(game.did_win, won, lost, game.overtime)?;
+    }
+
+    if !store.replace(&games[index], &game)? {
+        return Err(Error::GameNotFound(games[index].to_string()));
+    }
+    games[index] = game.clone();
+    games.sort_by_key(|g| g.date_time);
+
+    tracing::info!(game.map=%game.map, game.did_win=game.did_win, "edited game");
+
+    let stats = Stats::new(
+        &tag_filter.apply(games),
+        Local::now(),
+        cod_version,
+        day_boundary,
+    )?;
+    print_game_saved(&game, &stats);
+    Ok(())
+}
+
+/// A time window with the query it was turned into and the stats of the games it holds.
+type WindowStats = (TimeWindow, GameQuery, StatsGroup);
+
+/// Looks up the games in `window` from the store rather than going through every game.
+fn window_stats(
+    store: &mut dyn GameStore,
+    window: TimeWindow,
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    tag_filter: &TagFilter,
+) -> Result<WindowStats, Error> {
+    let query = window.query(Local::now(), cod_version, day_boundary)?;
+    let stats = StatsGroup::for_query(store, cod_version, &query, tag_filter)?;
+    Ok((window, query, stats))
+}
+
+#[instrument(skip(games))]
+fn run_stats(
+    games: &[GamePlayed],
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    format: StatsFormat,
+    map_sort: MapSort,
+    window: Option<WindowStats>,
+) -> Result<(), Error> {
+    let stats = Stats::new(games, Local::now(), cod_version, day_boundary)?;
+
+    if format == StatsFormat::Table {
+        display_stats(
+            &stats,
+            &Form::recent(&filter_by_version(games, cod_version)),
+        );
+        stats.display_map_stats(map_sort);
+        if let Some((window, _, window_stats)) = &window {
+            display_window_stats(window, window_stats, map_sort);
+        }
+        return Ok(());
+    }
+
+    let mut report = StatsReport::new(&stats, cod_version, map_sort).with_teammates(
+        &teammate_stats(&filter_by_version(games, cod_version)),
+        map_sort,
+    );
+    if let Some((window, query, window_stats)) = &window {
+        report = report.with_window(window, query, window_stats, map_sort);
+    }
+    let mut out = std::io::stdout().lock();
+    match format {
+        StatsFormat::Json => report.write_json(&mut out)?,
+        StatsFormat::Yaml => report.write_yaml(&mut out)?,
+        StatsFormat::Csv | StatsFormat::Table => report.write_csv(&mut out)?,
+    }
+    Ok(())
+}
+
+#[instrument(skip(games))]
+fn run_recommend(
+    games: &[GamePlayed],
+    cod_version: &CodVersion,
+    maps: Vec<GunfightMap>,
+    half_life_days: u32,
+) -> Result<(), Error> {
+    maps.iter()
+        .try_for_each(|map| GunfightMap::validate(map, cod_version))?;
+    let maps = if maps.is_empty() {
+        GunfightMap::playable_in(cod_version)
+    } else {
+        maps
+    };
+
+    display_recommendations(&recommend_maps(
+        &filter_by_version(games, cod_version),
+        &maps,
+        Local::now(),
+        half_life_days,
+    ));
+    Ok(())
+}
+
+#[instrument(skip(games))]
+fn run_sessions(
+    games: &[GamePlayed],
+    cod_version: &CodVersion,
+    format: StatsFormat,
+    gap: i64,
+) -> Result<(), Error> {
+    let sessions = find_sessions(
+        &filter_by_version(games, cod_version),
+        Duration::minutes(gap),
+    );
+    let mut out = std::io::stdout().lock();
+    match format {
+        StatsFormat::Table => display_sessions(&sessions),
+        StatsFormat::Json => {
+            serde_json::to_writer_pretty(&mut out, &sessions).map_err(Error::SerializeStats)?;
+            println!();
+        }
+        StatsFormat::Yaml => serde_yaml::to_writer(&mut out, &sessions)?,
+        StatsFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(out);
+            for session in &sessions {
+                writer.serialize(session)?;
+            }
+            writer.flush().map_err(csv::Error::from)?;
+        }
+    }
+    Ok(())
+}
+
+#[instrument(skip(store, games))]
+fn run_import(
+    store: &mut dyn GameStore,
+    games: &mut Vec<GamePlayed>,
+    cod_version: &CodVersion,
+    file: &Path,
+) -> Result<(), Error> {
+    let reader = File::open(file).map_err(csv::Error::from)?;
+    let (imported, rejected) = csv_file::read_games(reader, cod_version)?;
+
+    // A row repeated within the file is a duplicate too, not only rows already recorded.
+    let mut duplicates = Vec::new();
+    let mut new: Vec<GamePlayed> = Vec::new();
+    for game in imported {
+        if games.contains(&game) || new.contains(&game) {
+            duplicates.push(game);
+        } else {
+            new.push(game);
+        }
+    }
+    store.append_all(&new)?;
+    games.extend_from_slice(&new);
+    games.sort_by_key(|g| g.date_time);
+
+    tracing::info!(
+        imported = new.len(),
+        duplicates = duplicates.len(),
+        rejected = rejected.len(),
+        "imported games"
+    );
+    println!(
+        "Imported {} games, skipped {} already recorded.",
+        new.len(),
+        duplicates.len()
+    );
+    if !rejected.is_empty() {
+        println!(
+            "{} rows could not be imported into {cod_version}:",
+            rejected.len()
+        );
+        rejected.iter().for_each(|row| println!("  {row}"));
+    }
+    Ok(())
 }
 
 #[instrument(skip(games))]
+fn run_export(
+    games: &[GamePlayed],
+    cod_version: &CodVersion,
+    file: Option<&Path>,
+) -> Result<(), Error> {
+    let games = filter_by_version(games, cod_version);
+    match file {
+        Some(file) => {
+            let writer = File::create(file).map_err(csv::Error::from)?;
+            csv_file::write_games(writer, games.iter().copied())?;
+            println!(
+                "Exported {} games to {}",
+                games.len(),
+                file.to_string_lossy()
+            );
+        }
+        None => csv_file::write_games(std::io::stdout().lock(), games.iter().copied())?,
+    }
+    Ok(())
+}
+
+#[instrument(skip(store, games))]
 fn run_main_menu(
     file_path: &Path,
+    store: &mut dyn GameStore,
     games: &mut Vec<GamePlayed>,
     cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    tag_filter: &TagFilter,
 ) -> Result<(), Error> {
-    let mut stats = Stats::new(games, Local::now(), cod_version)?;
+    let mut stats = Stats::new(
+        &tag_filter.apply(games),
+        Local::now(),
+        cod_version,
+        day_boundary,
+    )?;
     loop {
         match Select::new(
             &format!(
@@ -93,11 +595,38 @@ fn run_main_menu(
         )
         .prompt()?
         {
+            MainMenuOption::Recommend => {
+                option_recommend(&tag_filter.apply(games), cod_version)?;
+            }
             MainMenuOption::DisplayStats => {
-                option_display_stats(&stats, cod_version)?;
+                option_display_stats(
+                    store,
+                    &tag_filter.apply(games),
+                    &stats,
+                    cod_version,
+                    day_boundary,
+                    tag_filter,
+                )?;
             }
             MainMenuOption::EnterGames => {
-                option_enter_games(games, &mut stats, file_path, cod_version)?;
+                option_enter_games(
+                    store,
+                    games,
+                    &mut stats,
+                    cod_version,
+                    day_boundary,
+                    tag_filter,
+                )?;
+            }
+            MainMenuOption::Undo => {
+                option_undo(
+                    store,
+                    games,
+                    &mut stats,
+                    cod_version,
+                    day_boundary,
+                    tag_filter,
+                )?;
             }
             MainMenuOption::Back => break,
         }
@@ -105,11 +634,49 @@ fn run_main_menu(
     Ok(())
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Eq)]
+#[derive(Serialize, Deserialize, Debug, Clone, Eq, Default)]
 pub struct GamePlayed {
     pub map: GunfightMap,
     pub did_win: bool,
     pub date_time: DateTime<Local>,
+    /// Rounds we took, only known for games recorded with their score.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounds_won: Option<u8>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rounds_lost: Option<u8>,
+    /// Whether the game went to the deciding round.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub overtime: bool,
+    /// Personal combat stats, only known for games recorded with them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub kills: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deaths: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub assists: Option<u32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage: Option<u32>,
+    /// Who the game was played with, when recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub teammate: Option<String>,
+    /// Weapons played with, when recorded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub weapons: Vec<String>,
+    /// Labels to include or leave out games by, like `lag`, always lowercase.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+}
+
+impl GamePlayed {
+    /// The round score as won - lost, when both are known.
+    pub const fn round_score(&self) -> Option<(u8, u8)> {
+        match (self.rounds_won, self.rounds_lost) {
+            (Some(won), Some(lost)) => Some((won, lost)),
+            _ => None,
+        }
+    }
 }
 
 impl PartialEq for GamePlayed {
@@ -118,8 +685,81 @@ impl PartialEq for GamePlayed {
     }
 }
 
-#[instrument(skip(stats))]
-fn option_display_stats(stats: &Stats, cod_version: &CodVersion) -> Result<(), Error> {
+impl std::fmt::Display for GamePlayed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}{} on {} at {}",
+            if self.did_win { "Win" } else { "Loss" },
+            self.round_score()
+                .map(|(won, lost)| format!(
+                    " {won} - {lost}{}",
+                    if self.overtime { " (OT)" } else { "" }
+                ))
+                .unwrap_or_default(),
+            self.map,
+            self.date_time.to_rfc3339(),
+        )
+    }
+}
+
+/// Points at a single recorded game, either by its position in the stat sheet or by when it was
+/// played.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GameSelector {
+    Index(usize),
+    DateTime(DateTime<Local>),
+}
+
+impl FromStr for GameSelector {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Ok(index) = s.parse::<usize>() {
+            return Ok(Self::Index(index));
+        }
+        s.parse::<DateTime<Local>>()
+            .map(Self::DateTime)
+            .map_err(|_| format!("[{s}] is neither a game index nor an RFC 3339 timestamp"))
+    }
+}
+
+impl std::fmt::Display for GameSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Index(index) => write!(f, "index {index}"),
+            Self::DateTime(date_time) => write!(f, "{}", date_time.to_rfc3339()),
+        }
+    }
+}
+
+impl GameSelector {
+    /// Position in `games` of the selected game, indexes count only the games of `cod_version`.
+    pub fn position(&self, games: &[GamePlayed], cod_version: &CodVersion) -> Result<usize, Error> {
+        let index = match self {
+            Self::Index(index) => games
+                .iter()
+                .enumerate()
+                .filter(|(_, g)| GunfightMap::is_in(&g.map, cod_version))
+                .nth(*index)
+                .map(|(i, _)| i),
+            Self::DateTime(date_time) => games.iter().position(|g| g.date_time == *date_time),
+        }
+        .ok_or_else(|| Error::GameNotFound(self.to_string()))?;
+        GunfightMap::validate(&games[index].map, cod_version)?;
+        Ok(index)
+    }
+}
+
+#[instrument(skip(store, games, stats))]
+fn option_display_stats(
+    store: &mut dyn GameStore,
+    games: &[GamePlayed],
+    stats: &Stats,
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    tag_filter: &TagFilter,
+) -> Result<(), Error> {
     loop {
         match Select::new(
             "What would you like to do?",
@@ -128,19 +768,57 @@ fn option_display_stats(stats: &Stats, cod_version: &CodVersion) -> Result<(), E
         .prompt()?
         {
             DisplayStatsOption::Today => {
-                let mut table = build_stat_table(&stats.today);
+                let mut table = build_stat_table(&stats.today, &[]);
                 table.set_format(*FORMAT_BOX_CHARS);
                 table.printstd();
             }
-            DisplayStatsOption::Lifetime => display_stats(stats),
+            DisplayStatsOption::Lifetime => {
+                display_stats(stats, &Form::recent(&filter_by_version(games, cod_version)))
+            }
+            DisplayStatsOption::TimeWindow => {
+                let window = TimeWindow::get_window_choice(cod_version)?;
+                let query = window.query(Local::now(), cod_version, day_boundary)?;
+                display_window_stats(
+                    &window,
+                    &StatsGroup::for_query(store, cod_version, &query, tag_filter)?,
+                    MapSort::default(),
+                );
+            }
             DisplayStatsOption::OneMap => {
                 let map = GunfightMap::get_map_choice(cod_version)?;
                 if let Some(map_stats) = stats.lifet.get_map_stats(&map) {
                     println!("{map}: {map_stats}");
                 }
             }
+            DisplayStatsOption::Sessions => display_sessions(&find_sessions(
+                &filter_by_version(games, cod_version),
+                Duration::minutes(DEFAULT_SESSION_GAP_MINUTES),
+            )),
+            DisplayStatsOption::Streaks => display_streaks(&StreakDistribution::new(
+                &filter_by_version(games, cod_version),
+            )),
+            DisplayStatsOption::Trends => {
+                let period = Select::new("Per?", Period::iter().collect()).prompt()?;
+                display_trends(&group_by_period(
+                    &filter_by_version(games, cod_version),
+                    period,
+                    day_boundary,
+                ));
+            }
+            DisplayStatsOption::TimeOfDay => display_time_of_day(&TimeOfDayStats::new(
+                &filter_by_version(games, cod_version),
+                day_boundary,
+            )),
+            DisplayStatsOption::Teammates => {
+                display_teammates(&teammate_stats(&filter_by_version(games, cod_version)))
+            }
+            DisplayStatsOption::Loadouts => {
+                let games = filter_by_version(games, cod_version);
+                display_loadouts(&by_weapon(&games), &by_map_and_weapon(&games));
+            }
             DisplayStatsOption::Maps => {
-                stats.display_map_stats();
+                let sort = Select::new("Sort by?", MapSort::iter().collect()).prompt()?;
+                stats.display_map_stats(sort);
             }
             DisplayStatsOption::CurrentStreak => {
                 println!(
@@ -163,12 +841,60 @@ fn option_display_stats(stats: &Stats, cod_version: &CodVersion) -> Result<(), E
     Ok(())
 }
 
-#[instrument(skip(games, stats))]
+#[instrument(skip(games))]
+fn option_recommend(games: &[GamePlayed], cod_version: &CodVersion) -> Result<(), Error> {
+    let maps = MultiSelect::new(
+        "Which maps are up for vote? (none for all)",
+        GunfightMap::playable_in(cod_version),
+    )
+    .prompt()?;
+    run_recommend(games, cod_version, maps, DEFAULT_HALF_LIFE_DAYS)
+}
+
+#[instrument(skip(store, games, stats))]
+fn option_undo(
+    store: &mut dyn GameStore,
+    games: &mut Vec<GamePlayed>,
+    stats: &mut Stats,
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    tag_filter: &TagFilter,
+) -> Result<(), Error> {
+    let Some(index) = last_in_version(games, cod_version) else {
+        println!("No games to undo.");
+        return Ok(());
+    };
+    let game = &games[index];
+
+    if Confirm::new(&format!("Remove {game}?"))
+        .with_default(false)
+        .prompt()?
+    {
+        if !store.delete(game)? {
+            return Err(Error::GameNotFound(game.to_string()));
+        }
+        let game = games.remove(index);
+        tracing::info!(game.map=%game.map, game.did_win=game.did_win, "removed game");
+
+        *stats = Stats::new(
+            &tag_filter.apply(games),
+            Local::now(),
+            cod_version,
+            day_boundary,
+        )?;
+        println!("Removed {game}. {}.", streak_message(&stats.lifet));
+    }
+    Ok(())
+}
+
+#[instrument(skip(store, games, stats))]
 fn option_enter_games(
+    store: &mut dyn GameStore,
     games: &mut Vec<GamePlayed>,
     stats: &mut Stats,
-    file_path: &Path,
     cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+    tag_filter: &TagFilter,
 ) -> Result<(), Error> {
     loop {
         match GunfightMap::get_map_choice(cod_version)? {
@@ -183,70 +909,390 @@ fn option_enter_games(
                 let time = Local::now();
                 let did_win =
                     Select::new("Did you win?", DidWinOption::iter().collect()).prompt()?;
-                let game = match did_win {
+                let mut game = match did_win {
                     DidWinOption::Yes => GamePlayed {
                         map,
                         did_win: true,
                         date_time: time,
+                        ..Default::default()
                     },
                     DidWinOption::No => GamePlayed {
                         map,
                         did_win: false,
                         date_time: time,
+                        ..Default::default()
                     },
                     DidWinOption::Back => break,
                 };
+                prompt_notes_and_tags(&mut game, &known_tags(games))?;
+                prompt_round_score(&mut game)?;
+                prompt_combat(&mut game)?;
+                prompt_teammate(&mut game, &roster(games))?;
+                prompt_weapons(&mut game, &catalog(games, cod_version))?;
 
+                store.append(&game)?;
                 games.push(game.clone());
-                save(games, file_path);
 
                 tracing::info!(game.map=%game.map, game.did_win=game.did_win, "recorded game");
 
-                if game.did_win {
-                    stats.add_win(&game, &game.date_time.format(DAY_FMT).to_string())?;
-                } else {
-                    stats.add_loss(&game, &game.date_time.format(DAY_FMT).to_string())?;
-                }
+                // Rebuilt rather than added to, today may have rolled over since the menu opened.
+                let counted = tag_filter.apply(games);
+                *stats = Stats::new(&counted, game.date_time, cod_version, day_boundary)?;
 
-                display_stats(stats);
-                println!(
-                    "{} on {} saved. {} Streak now {}.",
-                    if game.did_win { "Win" } else { "Loss" },
-                    game.map,
-                    if stats.today.last_was_win {
-                        "Winning"
-                    } else {
-                        "Losing"
-                    },
-                    if stats.today.last_was_win {
-                        stats.lifet.win_streak
-                    } else {
-                        stats.lifet.loss_streak
-                    },
+                display_stats(
+                    stats,
+                    &Form::recent(&filter_by_version(&counted, cod_version)),
+                );
+                print_game_saved(&game, stats);
+                print_tilt_warning(
+                    &check_tilt(&filter_by_version(&counted, cod_version), &stats.lifet),
+                    &stats.today,
                 );
-                {
-                    let map_stats = stats.lifet.get_map_stats(&game.map).expect(
-                                "Could not find stats for this map for some reason. This is probably a bug.",
-                            );
-                    println!();
-                    println!("{}: {} - {}", &game.map, &map_stats.wins, &map_stats.losses);
-                    println!();
-                }
             }
         }
     }
     Ok(())
 }
 
+fn print_game_saved(game: &GamePlayed, stats: &Stats) {
+    println!(
+        "{} on {} saved. {}.",
+        if game.did_win { "Win" } else { "Loss" },
+        game.map,
+        streak_message(&stats.lifet),
+    );
+    if let Some(map_stats) = stats.lifet.get_map_stats(&game.map) {
+        println!();
+        println!("{}: {} - {}", &game.map, &map_stats.wins, &map_stats.losses);
+        println!();
+    }
+}
+
+fn print_tilt_warning(reasons: &[TiltReason], today: &StatsGroup) {
+    if reasons.is_empty() {
+        return;
+    }
+
+    println!("[WARNING]: You are going cold, time for a break.");
+    for reason in reasons {
+        println!("  - {reason}");
+    }
+    if today.wins > today.losses {
+        println!(
+            "  Still {} - {} today, stop now and keep it a good day.",
+            today.wins, today.losses
+        );
+    }
+    println!();
+}
+
+fn streak_message(stats: &StatsGroup) -> String {
+    format!(
+        "{} Streak now {}",
+        if stats.last_was_win {
+            "Winning"
+        } else {
+            "Losing"
+        },
+        if stats.last_was_win {
+            stats.win_streak
+        } else {
+            stats.loss_streak
+        },
+    )
+}
+
 #[instrument(skip(stats))]
-fn display_stats(stats: &Stats) {
+fn display_stats(stats: &Stats, form: &[Form]) {
+    println!();
+    build_final_table(stats, form).printstd();
+    println!();
+}
+
+fn display_window_stats(window: &TimeWindow, stats: &StatsGroup, map_sort: MapSort) {
+    println!();
+    println!("{window}:\n---");
+    let mut table = build_stat_table(stats, &[]);
+    table.set_format(*FORMAT_BOX_CHARS);
+    table.printstd();
+    stats
+        .get_map_stats_sorted_by(map_sort)
+        .iter()
+        .for_each(|m| {
+            println!("{}: {}", m.0, m.1);
+        });
+    println!();
+}
+
+fn display_recommendations(recommendations: &[MapRecommendation]) {
+    let mut table = Table::new();
+    table.set_format(*FORMAT_BOX_CHARS);
+    table.set_titles(row![
+        "Rank", "Map", "Verdict", "Score", "Games", "W - L", "Recent"
+    ]);
+    for (rank, recommendation) in recommendations.iter().enumerate() {
+        let verdict = recommendation.verdict.to_string();
+        table.add_row(Row::new(vec![
+            Cell::new(&(rank + 1).to_string()),
+            Cell::new(&recommendation.map.to_string()),
+            match recommendation.verdict {
+                Verdict::Vote => win_cell(&verdict),
+                Verdict::Avoid => loss_cell(&verdict),
+                Verdict::Neutral | Verdict::NoData => Cell::new(&verdict),
+            },
+            Cell::new(&format!("{:.0} %", recommendation.score)),
+            Cell::new(&recommendation.games().to_string()),
+            Cell::new(&format!(
+                "{} - {}",
+                recommendation.wins, recommendation.losses
+            )),
+            Cell::new(&recommendation.recent_form_string()),
+        ]));
+    }
+    println!();
+    table.printstd();
+    println!();
+}
+
+fn display_sessions(sessions: &[Session]) {
+    if sessions.is_empty() {
+        println!("No sessions played yet.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*FORMAT_BOX_CHARS);
+    table.set_titles(row![
+        "Started",
+        "Minutes",
+        "Games",
+        "W - L",
+        "Dub %",
+        "Best Streak",
+        "Best Map",
+        "Worst Map"
+    ]);
+    for session in sessions {
+        table.add_row(row![
+            session.start.format("%m-%d-%Y %H:%M"),
+            r->session.duration_minutes,
+            r->session.games,
+            format!("{} - {}", session.wins, session.losses),
+            r->format!("{:.2}", session.win_percentage),
+            r->session.best_win_streak,
+            session.best_map.as_ref().map(ToString::to_string).unwrap_or_default(),
+            session.worst_map.as_ref().map(ToString::to_string).unwrap_or_default(),
+        ]);
+    }
+    println!();
+    table.printstd();
+    println!();
+}
+
+fn display_trends(periods: &[PeriodStats]) {
+    if periods.is_empty() {
+        println!("No games played yet.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*FORMAT_BOX_CHARS);
+    table.set_titles(row![
+        "Period",
+        "Games",
+        "Dub's",
+        "L's",
+        "Dub %",
+        "Longest Dub Streak",
+        "Top Map"
+    ]);
+    for period in periods {
+        let stats = &period.stats;
+        table.add_row(Row::new(vec![
+            Cell::new(&period.period),
+            Cell::new(&(stats.wins + stats.losses).to_string()),
+            win_cell(&stats.wins.to_string()),
+            loss_cell(&stats.losses.to_string()),
+            win_cell(&format!("{:.2}", stats.get_win_percentage())),
+            win_cell(&stats.high_win_streak.to_string()),
+            Cell::new(
+                &period
+                    .top_map()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+            ),
+        ]));
+    }
+    println!();
+    table.printstd();
+    println!();
+}
+
+fn display_streaks(streaks: &StreakDistribution) {
+    if streaks.games() == 0 {
+        println!("No games played yet.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*FORMAT_BOX_CHARS);
+    table.set_titles(row![
+        "Length",
+        "Dub Streaks",
+        "Expected",
+        "L Streaks",
+        "Expected"
+    ]);
+    for count in streaks.counts() {
+        table.add_row(Row::new(vec![
+            Cell::new(&count.length.to_string()),
+            win_cell(&count.wins.to_string()),
+            win_cell(&format!("{:.1}", count.expected_wins)),
+            loss_cell(&count.losses.to_string()),
+            loss_cell(&format!("{:.1}", count.expected_losses)),
+        ]));
+    }
+    println!();
+    table.printstd();
+    println!();
+    println!(
+        "Average Dub streak {:.2} (expected {:.2}), average L streak {:.2} (expected {:.2}).",
+        streaks.average_win_streak(),
+        streaks.expected_average_win_streak(),
+        streaks.average_loss_streak(),
+        streaks.expected_average_loss_streak(),
+    );
+    println!(
+        "{} streaks against {:.1} expected by chance{}: {}.",
+        streaks.runs(),
+        streaks.expected_runs(),
+        streaks
+            .runs_z_score()
+            .map(|z| format!(" (z = {z:.2})"))
+            .unwrap_or_default(),
+        streaks.verdict(),
+    );
+    println!();
+}
+
+fn display_time_of_day(stats: &TimeOfDayStats) {
+    let by_hour = stats.by_hour();
+    if by_hour.is_empty() {
+        println!("No games played yet.");
+        return;
+    }
+
+    let mut hours = Table::new();
+    hours.set_format(*FORMAT_BOX_CHARS);
+    hours.set_titles(row!["Hour", "Games", "Dub's", "L's", "Dub %"]);
+    for (hour, map_stats) in &by_hour {
+        hours.add_row(time_bucket_row(&format!("{hour:02}:00"), map_stats));
+    }
+
+    let mut weekdays = Table::new();
+    weekdays.set_format(*FORMAT_BOX_CHARS);
+    weekdays.set_titles(row!["Day", "Games", "Dub's", "L's", "Dub %"]);
+    for (weekday, map_stats) in &stats.by_weekday() {
+        weekdays.add_row(time_bucket_row(&weekday.to_string(), map_stats));
+    }
+
+    println!();
+    hours.printstd();
+    println!();
+    weekdays.printstd();
+    println!();
+}
+
+fn display_teammates(teammates: &[TeammateStats]) {
+    if teammates.is_empty() {
+        println!("No games recorded with a teammate yet.");
+        return;
+    }
+
+    let mut table = Table::new();
+    table.set_format(*FORMAT_BOX_CHARS);
+    table.set_titles(row![
+        "Teammate",
+        "Games",
+        "Dub's",
+        "L's",
+        "Dub %",
+        "Best Maps"
+    ]);
+    for teammate in teammates {
+        let stats = &teammate.stats;
+        table.add_row(Row::new(vec![
+            Cell::new(&teammate.teammate),
+            Cell::new(&(stats.wins + stats.losses).to_string()),
+            win_cell(&stats.wins.to_string()),
+            loss_cell(&stats.losses.to_string()),
+            win_cell(&format!("{:.2}", stats.get_win_percentage())),
+            Cell::new(
+                &teammate
+                    .best_maps()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            ),
+        ]));
+    }
+
+    println!();
+    table.printstd();
+    println!();
+}
+
+fn display_loadouts(weapons: &[LoadoutStats], map_weapons: &[LoadoutStats]) {
+    if weapons.is_empty() {
+        println!("No games recorded with weapons yet.");
+        return;
+    }
+
+    let mut by_weapon = Table::new();
+    by_weapon.set_format(*FORMAT_BOX_CHARS);
+    by_weapon.set_titles(row!["Weapon", "Games", "Dub's", "L's", "Dub %"]);
+    for loadout in weapons {
+        by_weapon.add_row(time_bucket_row(&loadout.weapon, &loadout.stats));
+    }
+
+    let mut by_map = Table::new();
+    by_map.set_format(*FORMAT_BOX_CHARS);
+    by_map.set_titles(row!["Map", "Weapon", "Games", "Dub's", "L's", "Dub %"]);
+    for loadout in map_weapons {
+        let mut row = time_bucket_row(&loadout.weapon, &loadout.stats);
+        row.insert_cell(
+            0,
+            Cell::new(
+                &loadout
+                    .map
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default(),
+            ),
+        );
+        by_map.add_row(row);
+    }
+
     println!();
-    build_final_table(stats).printstd();
+    by_weapon.printstd();
     println!();
+    by_map.printstd();
+    println!();
+}
+
+fn time_bucket_row(label: &str, map_stats: &MapStats) -> Row {
+    Row::new(vec![
+        Cell::new(label),
+        Cell::new(&(map_stats.wins + map_stats.losses).to_string()),
+        win_cell(&map_stats.wins.to_string()),
+        loss_cell(&map_stats.losses.to_string()),
+        win_cell(&format!("{:.2}", map_stats.get_win_percentage())),
+    ])
 }
 
 #[instrument(skip(stats))]
-fn build_final_table(stats: &Stats) -> Table {
+fn build_final_table(stats: &Stats, form: &[Form]) -> Table {
     let mut lifetime_title_cell = Cell::new("Lifetime Stats")
         .with_style(Attr::Bold)
         // .with_style(Attr::Italic(true))
@@ -261,8 +1307,8 @@ fn build_final_table(stats: &Stats) -> Table {
     let mut wrapper_table = Table::new();
     wrapper_table.add_row(Row::new(vec![today_title_cell, lifetime_title_cell]));
 
-    let lifetime = build_stat_table(&stats.lifet);
-    let daily = build_stat_table(&stats.today);
+    let lifetime = build_stat_table(&stats.lifet, form);
+    let daily = build_stat_table(&stats.today, &[]);
 
     wrapper_table.set_format(*format::consts::FORMAT_CLEAN);
     wrapper_table.add_row(row![daily, lifetime]);
@@ -270,42 +1316,268 @@ fn build_final_table(stats: &Stats) -> Table {
     wrapper_table
 }
 
-fn build_stat_table(stats: &StatsGroup) -> Table {
+/// Form rows are only added for the `form` given, which should be taken from the same games as
+/// `stats`.
+fn build_stat_table(stats: &StatsGroup, form: &[Form]) -> Table {
     let mut table = Table::new();
     table.add_row(Row::new(vec![
         Cell::new("Dub's"),
-        Cell::new(&stats.wins.to_string())
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::GREEN)),
+        win_cell(&stats.wins.to_string()),
     ]));
     table.add_row(Row::new(vec![
         Cell::new("Dub %"),
-        Cell::new(&format!("{:.2}", &stats.get_win_percentage()))
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::GREEN)),
+        win_cell(&format!("{:.2}", &stats.get_win_percentage())),
     ]));
+    table.add_row(Row::new(vec![
+        Cell::new("Likely Dub %"),
+        Cell::new(&stats.get_win_interval().to_string()),
+    ]));
+    for form in form {
+        let cell = format!(
+            "{:.2} {}",
+            form.win_percentage,
+            form.arrow(stats.get_win_percentage())
+        );
+        table.add_row(Row::new(vec![
+            Cell::new(&format!("Form (last {})", form.games)),
+            if form.win_percentage >= stats.get_win_percentage() {
+                win_cell(&cell)
+            } else {
+                loss_cell(&cell)
+            },
+        ]));
+    }
     table.add_row(Row::new(vec![
         Cell::new("Longest Dub Streak"),
-        Cell::new(&stats.high_win_streak.to_string())
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::GREEN)),
+        win_cell(&stats.high_win_streak.to_string()),
     ]));
     table.add_row(Row::new(vec![
         Cell::new("L's"),
-        Cell::new(&stats.losses.to_string())
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::RED)),
+        loss_cell(&stats.losses.to_string()),
     ]));
     table.add_row(Row::new(vec![
         Cell::new("Longest L-L-L Streak"),
-        Cell::new(&stats.high_loss_streak.to_string())
-            .with_style(Attr::Bold)
-            .with_style(Attr::ForegroundColor(color::RED)),
+        loss_cell(&stats.high_loss_streak.to_string()),
     ]));
+    if stats.rounds.games > 0 {
+        let rounds = &stats.rounds;
+        let differential = format!(
+            "{:+} ({:+.1} a game)",
+            rounds.differential(),
+            rounds.average_differential()
+        );
+        table.add_row(Row::new(vec![
+            Cell::new("Round Diff"),
+            if rounds.differential() >= 0 {
+                win_cell(&differential)
+            } else {
+                loss_cell(&differential)
+            },
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Close Games"),
+            Cell::new(&format!("{} - {}", rounds.close_wins, rounds.close_losses)),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Overtime"),
+            Cell::new(&format!(
+                "{} - {}",
+                rounds.overtime_wins, rounds.overtime_losses
+            )),
+        ]));
+    }
 
+    if stats.combat.games > 0 {
+        let combat = &stats.combat;
+        table.add_row(Row::new(vec![
+            Cell::new("K/D"),
+            Cell::new(&format!(
+                "{:.2} ({} - {})",
+                combat.kd_ratio(),
+                combat.kills,
+                combat.deaths
+            )),
+        ]));
+        table.add_row(Row::new(vec![
+            Cell::new("Per Game"),
+            Cell::new(&format!(
+                "{:.1} K / {:.1} D / {:.1} A / {:.0} Dmg",
+                combat.kills_per_game(),
+                combat.deaths_per_game(),
+                combat.assists_per_game(),
+                combat.damage_per_game()
+            )),
+        ]));
+    }
     // table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
     table.set_format(*format::consts::FORMAT_NO_LINESEP_WITH_TITLE);
     table.set_format(*FORMAT_BOX_CHARS);
 
     table
 }
+
+fn win_cell(text: &str) -> Cell {
+    Cell::new(text)
+        .with_style(Attr::Bold)
+        .with_style(Attr::ForegroundColor(color::GREEN))
+}
+
+fn loss_cell(text: &str) -> Cell {
+    Cell::new(text)
+        .with_style(Attr::Bold)
+        .with_style(Attr::ForegroundColor(color::RED))
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    /// Keeps games in memory, `lost` games are left out of it behind the app's back.
+    #[derive(Default)]
+    struct MemoryStore {
+        games: Vec<GamePlayed>,
+        lost: Vec<GamePlayed>,
+    }
+
+    impl GameStore for MemoryStore {
+        fn load_all(&mut self) -> Result<Vec<GamePlayed>, Error> {
+            Ok(self.games.clone())
+        }
+
+        fn append(&mut self, game: &GamePlayed) -> Result<(), Error> {
+            self.games.push(game.clone());
+            Ok(())
+        }
+
+        fn delete(&mut self, game: &GamePlayed) -> Result<bool, Error> {
+            let found = !self.lost.contains(game) && self.games.contains(game);
+            self.games.retain(|g| g != game);
+            Ok(found)
+        }
+
+        fn replace(&mut self, old: &GamePlayed, new: &GamePlayed) -> Result<bool, Error> {
+            if self.lost.contains(old) {
+                return Ok(false);
+            }
+            let Some(stored) = self.games.iter_mut().find(|g| *g == old) else {
+                return Ok(false);
+            };
+            *stored = new.clone();
+            Ok(true)
+        }
+    }
+
+    fn games() -> Vec<GamePlayed> {
+        let start = Local.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        [GunfightMap::Shipment, GunfightMap::Rust, GunfightMap::Meat]
+            .into_iter()
+            .enumerate()
+            .map(|(i, map)| GamePlayed { map, did_win: i % 2 == 0, date_time: start + Duration::minutes(i as i64 * 10), ..Default::default() })
+            .collect()
+    }
+
+    #[test]
+    fn test_game_selector() -> Result<()> {
+        // Arrange
+        let games = games();
+        let second = games[1].date_time;
+
+        // Act / Assert
+        assert_eq!(GameSelector::from_str("1"), Ok(GameSelector::Index(1)));
+        assert_eq!(GameSelector::from_str(&second.to_rfc3339()), Ok(GameSelector::DateTime(second)));
+        assert!(GameSelector::from_str("yesterday").is_err());
+        assert_eq!(GameSelector::Index(2).position(&games, &CodVersion::MW3)?, 2);
+        assert_eq!(GameSelector::DateTime(second).position(&games, &CodVersion::MW3)?, 1);
+        assert!(matches!(GameSelector::Index(3).position(&games, &CodVersion::MW3), Err(Error::GameNotFound(_))));
+        assert!(matches!(GameSelector::DateTime(second + Duration::seconds(1)).position(&games, &CodVersion::MW3), Err(Error::GameNotFound(_))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_delete_undo_and_edit() -> Result<()> {
+        // Arrange
+        let mut games = games();
+        let mut store = MemoryStore { games: games.clone(), ..Default::default() };
+        let day_boundary = DayBoundary::default();
+        let meat = games[2].clone();
+
+        // Act
+        run_delete(&mut store, &mut games, &CodVersion::MW3, &day_boundary, &TagFilter::default(), Some(&GameSelector::Index(0)))?;
+        run_edit(&mut store, &mut games, &CodVersion::MW3, &day_boundary, &TagFilter::default(), &GameSelector::Index(0), GameEdit { result: Some(GameResult::Win), rounds: Some((6, 5)), overtime: true, ..Default::default() })?;
+        run_delete(&mut store, &mut games, &CodVersion::MW3, &day_boundary, &TagFilter::default(), None)?;
+
+        // Assert
+        assert_eq!(games, store.games);
+        assert_eq!(games.len(), 1);
+        assert_eq!((&games[0].map, games[0].did_win, games[0].round_score(), games[0].overtime), (&GunfightMap::Rust, true, Some((6, 5)), true));
+        assert!(!store.games.contains(&meat));
+        Ok(())
+    }
+
+    #[test]
+    fn test_targets_only_games_of_the_version() -> Result<()> {
+        // Arrange
+        let start = Local.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        let atrium = GamePlayed { map: GunfightMap::Atrium, did_win: true, date_time: start, ..Default::default() };
+        let meat = GamePlayed { map: GunfightMap::Meat, did_win: true, date_time: start + Duration::minutes(10), ..Default::default() };
+        let mut games = vec![atrium.clone(), meat.clone()];
+        let mut store = MemoryStore { games: games.clone(), ..Default::default() };
+        let day_boundary = DayBoundary::default();
+
+        // Act
+        let edited = run_edit(&mut store, &mut games, &CodVersion::MW3, &day_boundary, &TagFilter::default(), &GameSelector::DateTime(atrium.date_time), GameEdit { result: Some(GameResult::Loss), ..Default::default() });
+        run_edit(&mut store, &mut games, &CodVersion::MW3, &day_boundary, &TagFilter::default(), &GameSelector::Index(0), GameEdit { result: Some(GameResult::Loss), ..Default::default() })?;
+        run_delete(&mut store, &mut games, &CodVersion::MW, &day_boundary, &TagFilter::default(), None)?;
+
+        // Assert
+        assert!(matches!(edited, Err(Error::MapNotInVersion(GunfightMap::Atrium, CodVersion::MW3))));
+        assert_eq!(GameSelector::Index(0).position(&[atrium, meat], &CodVersion::MW3)?, 1);
+        assert_eq!(games, store.games);
+        assert_eq!(games.iter().map(|g| (&g.map, g.did_win)).collect::<Vec<_>>(), vec![(&GunfightMap::Meat, false)]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_import_skips_repeated_rows() -> Result<()> {
+        // Arrange
+        let all = games();
+        let mut games = all[..2].to_vec();
+        let mut store = MemoryStore { games: games.clone(), ..Default::default() };
+        let path = std::env::temp_dir().join(format!("cod_keeper_import_{}.csv", std::process::id()));
+        csv_file::write_games(File::create(&path)?, [&all[2], &all[0], &all[2]])?;
+
+        // Act
+        run_import(&mut store, &mut games, &CodVersion::MW3, &path)?;
+        std::fs::remove_file(&path)?;
+
+        // Assert
+        assert_eq!(games, all);
+        assert_eq!(store.games, all);
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_game_in_store_is_not_found() -> Result<()> {
+        // Arrange
+        let mut games = games();
+        let mut store = MemoryStore { games: games.clone(), lost: vec![games[0].clone(), games[2].clone()] };
+        let day_boundary = DayBoundary::default();
+
+        // Act
+        let deleted = run_delete(&mut store, &mut games, &CodVersion::MW3, &day_boundary, &TagFilter::default(), Some(&GameSelector::Index(0)));
+        let edited = run_edit(&mut store, &mut games, &CodVersion::MW3, &day_boundary, &TagFilter::default(), &GameSelector::Index(2), GameEdit { result: Some(GameResult::Loss), ..Default::default() });
+
+        // Assert
+        assert!(matches!(deleted, Err(Error::GameNotFound(_))));
+        assert!(matches!(edited, Err(Error::GameNotFound(_))));
+        assert_eq!(games.len(), 3);
+        assert!(games[2].did_win);
+        Ok(())
+    }
+}
@@ -0,0 +1,149 @@
+use std::borrow::Cow;
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+use crate::{error::Error, run::GamePlayed};
+
+/// Version of the stat sheet layout written by this build.
+///
+/// Bump this together with a new entry in [`MIGRATIONS`] whenever the layout changes in a way
+/// older files can not be read as is.
+pub const SCHEMA_VERSION: u64 = 2;
+
+/// Upgrades a stat sheet from the version at its index to the next one.
+const MIGRATIONS: &[fn(Value) -> Value] = &[v0_to_v1, v1_to_v2];
+
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq)]
+pub struct StatSheet<'a> {
+    pub schema_version: u64,
+    pub games: Cow<'a, [GamePlayed]>,
+}
+
+impl<'a> StatSheet<'a> {
+    pub const fn new(games: &'a [GamePlayed]) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            games: Cow::Borrowed(games),
+        }
+    }
+}
+
+/// Version of a raw stat sheet, files written before the envelope existed are version 0.
+pub fn schema_version(sheet: &Value) -> Result<u64, Error> {
+    match sheet {
+        Value::Array(_) => Ok(0),
+        Value::Object(fields) => fields
+            .get("schema_version")
+            .and_then(Value::as_u64)
+            .ok_or(Error::UnknownStatsSchema),
+        _ => Err(Error::UnknownStatsSchema),
+    }
+}
+
+/// Runs every migration needed to bring `sheet` up to [`SCHEMA_VERSION`].
+///
+/// Returns the upgraded sheet and the version it started at.
+pub fn migrate(mut sheet: Value) -> Result<(Value, u64), Error> {
+    let found = schema_version(&sheet)?;
+    if found > SCHEMA_VERSION {
+        return Err(Error::UnsupportedStatsSchema {
+            found,
+            supported: SCHEMA_VERSION,
+        });
+    }
+
+    for (version, migration) in MIGRATIONS.iter().enumerate().skip(found as usize) {
+        tracing::info!(from = version, to = version + 1, "migrating stats file");
+        sheet = migration(sheet);
+    }
+
+    Ok((sheet, found))
+}
+
+/// Wraps the bare array of games in the versioned envelope.
+fn v0_to_v1(games: Value) -> Value {
+    json!({
+        "schema_version": 1,
+        "games": games,
+    })
+}
+
+/// Games gained round scores, combat stats, teammates, weapons, tags and notes. They are all
+/// optional so the games stay as they are, the bump only keeps older builds from dropping them.
+fn v1_to_v2(mut sheet: Value) -> Value {
+    sheet["schema_version"] = json!(2);
+    sheet
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_migrate_bare_array() -> Result<()> {
+        // Arrange
+        let legacy = json!([
+            { "map": "Asile9", "did_win": false, "date_time": "2023-09-30T13:07:37-04:00" },
+        ]);
+
+        // Act
+        let (sheet, found) = migrate(legacy.clone())?;
+
+        // Assert
+        assert_eq!(found, 0);
+        assert_eq!(
+            sheet,
+            json!({ "schema_version": SCHEMA_VERSION, "games": legacy })
+        );
+        assert_eq!(serde_json::from_value::<StatSheet>(sheet)?.games.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_v1() -> Result<()> {
+        // Arrange
+        let games = json!([
+            { "map": "Rust", "did_win": true, "date_time": "2024-06-28T11:38:46-04:00" },
+        ]);
+        let v1 = json!({ "schema_version": 1, "games": games });
+
+        // Act
+        let (sheet, found) = migrate(v1)?;
+
+        // Assert
+        assert_eq!(found, 1);
+        assert_eq!(sheet, json!({ "schema_version": 2, "games": games }));
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_current_is_untouched() -> Result<()> {
+        // Arrange
+        let current = json!({ "schema_version": SCHEMA_VERSION, "games": [] });
+
+        // Act
+        let (sheet, found) = migrate(current.clone())?;
+
+        // Assert
+        assert_eq!(found, SCHEMA_VERSION);
+        assert_eq!(sheet, current);
+        Ok(())
+    }
+
+    #[test]
+    fn test_migrate_newer_version_fails() {
+        // Arrange
+        let newer = json!({ "schema_version": SCHEMA_VERSION + 1, "games": [] });
+
+        // Act / Assert
+        assert!(matches!(
+            migrate(newer),
+            Err(Error::UnsupportedStatsSchema { .. })
+        ));
+    }
+}
@@ -0,0 +1,89 @@
+use chrono::{DateTime, Duration, Local};
+use serde::Serialize;
+
+use crate::{map::GunfightMap, run::GamePlayed, stats::StatsGroup};
+
+/// Minutes between two games after which the second one starts a new session.
+pub const DEFAULT_SESSION_GAP_MINUTES: i64 = 45;
+
+/// Games played without a break longer than the session gap.
+#[derive(Serialize, Debug, PartialEq)]
+pub struct Session {
+    pub start: DateTime<Local>,
+    pub end: DateTime<Local>,
+    pub duration_minutes: i64,
+    pub games: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_percentage: f32,
+    pub best_win_streak: usize,
+    pub best_map: Option<GunfightMap>,
+    pub worst_map: Option<GunfightMap>,
+}
+
+impl Session {
+    /// `games` must not be empty and be ordered oldest first.
+    fn new(games: &[&GamePlayed]) -> Option<Self> {
+        let start = games.first()?.date_time;
+        let end = games.last()?.date_time;
+        let stats = StatsGroup::from_games(games.iter().copied());
+        let map_stats = stats.get_sorted_map_stats();
+
+        Some(Self {
+            start,
+            end,
+            duration_minutes: (end - start).num_minutes(),
+            games: games.len(),
+            wins: stats.wins,
+            losses: stats.losses,
+            win_percentage: stats.get_win_percentage(),
+            best_win_streak: stats.high_win_streak,
+            best_map: map_stats.first().map(|(map, _)| (*map).clone()),
+            worst_map: map_stats.last().map(|(map, _)| (*map).clone()),
+        })
+    }
+}
+
+/// Splits `games`, ordered oldest first, into sessions wherever more than `gap` passed between
+/// two games.
+pub fn find_sessions(games: &[&GamePlayed], gap: Duration) -> Vec<Session> {
+    games
+        .chunk_by(|a, b| b.date_time - a.date_time <= gap)
+        .filter_map(Session::new)
+        .collect()
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_find_sessions() -> Result<()> {
+        // Arrange
+        let at = |h, m| Local.with_ymd_and_hms(2023, 12, 1, h, m, 0).unwrap();
+        let games = [
+            GamePlayed { did_win: true, map: GunfightMap::Shipment, date_time: at(20, 0), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Shipment, date_time: at(20, 10), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Rust, date_time: at(20, 55), ..Default::default() },
+            // Break
+            GamePlayed { did_win: false, map: GunfightMap::Meat, date_time: at(22, 0), ..Default::default() },
+        ];
+        let games = games.iter().collect::<Vec<_>>();
+
+        // Act
+        let sessions = find_sessions(&games, Duration::minutes(DEFAULT_SESSION_GAP_MINUTES));
+
+        // Assert
+        assert_eq!(sessions, vec![
+            Session { start: at(20, 0), end: at(20, 55), duration_minutes: 55, games: 3, wins: 2, losses: 1, win_percentage: 2.0 / 3.0 * 100.0, best_win_streak: 2, best_map: Some(GunfightMap::Shipment), worst_map: Some(GunfightMap::Rust) },
+            Session { start: at(22, 0), end: at(22, 0), duration_minutes: 0, games: 1, wins: 0, losses: 1, win_percentage: 0.0, best_win_streak: 0, best_map: Some(GunfightMap::Meat), worst_map: Some(GunfightMap::Meat) },
+        ]);
+        Ok(())
+    }
+}
@@ -0,0 +1,225 @@
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use tracing::instrument;
+
+use crate::{
+    error::Error,
+    run::GamePlayed,
+    schema,
+    store::{GameQuery, GameStore},
+};
+
+/// Stores games in an embedded SQLite database, so recording a game does not rewrite the whole
+/// history.
+///
+/// The columns used for lookups are kept next to the full game as JSON, new fields on
+/// [`GamePlayed`] do not need a table change.
+#[derive(Debug)]
+pub struct SqliteStore {
+    connection: Connection,
+}
+
+const CREATE_TABLES: &str = "
+    CREATE TABLE IF NOT EXISTS games (
+        id INTEGER PRIMARY KEY,
+        played_at INTEGER NOT NULL,
+        map TEXT NOT NULL,
+        did_win INTEGER NOT NULL,
+        game TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS games_played_at ON games (played_at);
+    CREATE INDEX IF NOT EXISTS games_map ON games (map);
+";
+
+/// Finds the row of a game, games are equal when their map, result and time are.
+const FIND_GAME: &str =
+    "SELECT id FROM games WHERE played_at = ?1 AND map = ?2 AND did_win = ?3 LIMIT 1";
+
+impl SqliteStore {
+    /// Opens the database at `file_path`. A missing database is only created when `create` is set,
+    /// so a mistyped path is not taken for a new, empty stat sheet.
+    #[instrument]
+    pub fn open(file_path: &Path, create: bool) -> Result<Self, Error> {
+        if !create && !file_path.exists() {
+            tracing::error!(stats_path=?file_path, "database does not exist");
+            return Err(Error::StatsFileNotFound(file_path.to_path_buf()));
+        }
+        let connection = Connection::open(file_path)?;
+
+        let found: u64 = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if found > schema::SCHEMA_VERSION {
+            return Err(Error::UnsupportedStatsSchema {
+                found,
+                supported: schema::SCHEMA_VERSION,
+            });
+        }
+
+        connection.execute_batch(CREATE_TABLES)?;
+        connection.pragma_update(None, "user_version", schema::SCHEMA_VERSION)?;
+
+        Ok(Self { connection })
+    }
+
+    fn find(&self, game: &GamePlayed) -> Result<Option<i64>, Error> {
+        Ok(self
+            .connection
+            .query_row(
+                FIND_GAME,
+                params![played_at(game), game.map.to_string(), game.did_win],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+}
+
+impl GameStore for SqliteStore {
+    fn load_all(&mut self) -> Result<Vec<GamePlayed>, Error> {
+        self.query(&GameQuery::default())
+    }
+
+    fn append(&mut self, game: &GamePlayed) -> Result<(), Error> {
+        insert(&self.connection, game)
+    }
+
+    fn append_all(&mut self, games: &[GamePlayed]) -> Result<(), Error> {
+        let transaction = self.connection.transaction()?;
+        games
+            .iter()
+            .try_for_each(|game| insert(&transaction, game))?;
+        transaction.commit()?;
+        Ok(())
+    }
+
+    fn delete(&mut self, game: &GamePlayed) -> Result<bool, Error> {
+        let Some(id) = self.find(game)? else {
+            return Ok(false);
+        };
+        self.connection
+            .execute("DELETE FROM games WHERE id = ?1", [id])?;
+        Ok(true)
+    }
+
+    fn replace(&mut self, old: &GamePlayed, new: &GamePlayed) -> Result<bool, Error> {
+        let Some(id) = self.find(old)? else {
+            return Ok(false);
+        };
+        self.connection.execute(
+            "UPDATE games SET played_at = ?1, map = ?2, did_win = ?3, game = ?4 WHERE id = ?5",
+            params![
+                played_at(new),
+                new.map.to_string(),
+                new.did_win,
+                serde_json::to_string(new).map_err(Error::SerializeStats)?,
+                id,
+            ],
+        )?;
+        Ok(true)
+    }
+
+    fn query(&mut self, query: &GameQuery) -> Result<Vec<GamePlayed>, Error> {
+        let mut statement = self.connection.prepare(
+            "SELECT game FROM games
+             WHERE (?1 IS NULL OR played_at >= ?1)
+               AND (?2 IS NULL OR played_at < ?2)
+               AND (?3 IS NULL OR map = ?3)
+             ORDER BY played_at, id",
+        )?;
+        let games = statement
+            .query_map(
+                params![
+                    query.since.map(|d| d.timestamp_micros()),
+                    query.until.map(|d| d.timestamp_micros()),
+                    query.map.as_ref().map(ToString::to_string),
+                ],
+                game_from_row,
+            )?
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(games)
+    }
+}
+
+fn insert(connection: &Connection, game: &GamePlayed) -> Result<(), Error> {
+    connection.execute(
+        "INSERT INTO games (played_at, map, did_win, game) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            played_at(game),
+            game.map.to_string(),
+            game.did_win,
+            serde_json::to_string(game).map_err(Error::SerializeStats)?,
+        ],
+    )?;
+    Ok(())
+}
+
+/// Games are ordered and filtered by the instant they were played, regardless of their offset.
+const fn played_at(game: &GamePlayed) -> i64 {
+    game.date_time.timestamp_micros()
+}
+
+fn game_from_row(row: &Row<'_>) -> rusqlite::Result<GamePlayed> {
+    let game: String = row.get(0)?;
+    serde_json::from_str(&game).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use crate::GunfightMap;
+
+    use super::*;
+
+    #[test]
+    fn test_sqlite_store_round_trip() -> Result<()> {
+        // Arrange
+        let mut store = SqliteStore { connection: Connection::open_in_memory()? };
+        store.connection.execute_batch(CREATE_TABLES)?;
+        let first = GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), ..Default::default() };
+        let second = GamePlayed { did_win: true, map: GunfightMap::Hill, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 2).unwrap(), ..Default::default() };
+        let edited = GamePlayed { did_win: true, map: GunfightMap::Pine, date_time: Local.with_ymd_and_hms(2023, 9, 25, 0, 0, 3).unwrap(), ..Default::default() };
+
+        // Act
+        store.append(&second)?;
+        store.append(&first)?;
+        let all = store.load_all()?;
+        let on_hill = store.query(&GameQuery { map: Some(GunfightMap::Hill), ..Default::default() })?;
+        let replaced = store.replace(&second, &edited)?;
+        let deleted = store.delete(&first)?;
+        let deleted_again = store.delete(&first)?;
+
+        // Assert
+        assert_eq!(all, vec![first, second.clone()]);
+        assert_eq!(on_hill, vec![second]);
+        assert!(replaced);
+        assert!(deleted);
+        assert!(!deleted_again);
+        assert_eq!(store.load_all()?, vec![edited]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_sqlite_store_open_missing() -> Result<()> {
+        // Arrange
+        let path = std::env::temp_dir().join(format!("cod_keeper_missing_{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        // Act
+        let missing = SqliteStore::open(&path, false);
+        let created = SqliteStore::open(&path, true).map(|mut store| store.load_all());
+        let reopened = SqliteStore::open(&path, false).is_ok();
+        std::fs::remove_file(&path)?;
+
+        // Assert
+        assert!(matches!(missing, Err(Error::StatsFileNotFound(_))));
+        assert!(matches!(created, Ok(Ok(games)) if games.is_empty()));
+        assert!(reopened);
+        Ok(())
+    }
+}
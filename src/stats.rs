@@ -2,9 +2,22 @@ use anyhow::Result;
 
 use std::collections::HashMap;
 
-use chrono::{DateTime, Local};
-
-use crate::{error::Error, map::MapStats, run::GamePlayed, CodVersion, GunfightMap, DAY_FMT};
+use chrono::{DateTime, Local, NaiveDate};
+use clap::ValueEnum;
+use strum_macros::{Display, EnumIter};
+
+use crate::{
+    combat::CombatStats,
+    confidence::WinInterval,
+    day_boundary::DayBoundary,
+    error::Error,
+    map::MapStats,
+    rounds::RoundStats,
+    run::GamePlayed,
+    store::{GameQuery, GameStore},
+    tags::TagFilter,
+    CodVersion, GunfightMap,
+};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Stats {
@@ -22,9 +35,126 @@ pub struct StatsGroup {
     pub loss_streak: usize,
     pub last_was_win: bool,
     pub map_stats: HashMap<GunfightMap, MapStats>,
+    pub rounds: RoundStats,
+    pub combat: CombatStats,
+}
+
+/// Order maps are listed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum, Display, EnumIter)]
+pub enum MapSort {
+    /// Highest win percentage first.
+    #[default]
+    #[strum(serialize = "Win %")]
+    WinPercentage,
+    /// Highest lower bound of the win percentage interval first, which needs many games to
+    /// rank high.
+    #[strum(serialize = "Lower Bound of Win %")]
+    LowerBound,
+}
+
+/// Keeps only the games played on maps that are available in `cod_version`.
+pub fn filter_by_version<'a>(
+    games: &'a [GamePlayed],
+    cod_version: &CodVersion,
+) -> Vec<&'a GamePlayed> {
+    games
+        .iter()
+        .filter(|g| {
+            let supported = GunfightMap::is_in(&g.map, cod_version);
+            if !supported {
+                log::error!(
+                    "Filtering map [{map}] that is not supported in [{cod_version}].",
+                    map = g.map
+                );
+            }
+            supported
+        })
+        .collect()
+}
+
+impl Default for StatsGroup {
+    fn default() -> Self {
+        Self {
+            wins: 0,
+            losses: 0,
+            high_win_streak: 0,
+            high_loss_streak: 0,
+            win_streak: 0,
+            loss_streak: 0,
+            last_was_win: true,
+            map_stats: HashMap::new(),
+            rounds: RoundStats::default(),
+            combat: CombatStats::default(),
+        }
+    }
 }
 
 impl StatsGroup {
+    /// Stats of `games`, which are expected to be ordered oldest first.
+    pub fn from_games<'a>(games: impl IntoIterator<Item = &'a GamePlayed>) -> Self {
+        let mut stats = Self::default();
+        for game in games {
+            if game.did_win {
+                stats.add_win(&game.map);
+            } else {
+                stats.add_loss(&game.map);
+            }
+            stats.add_details(game);
+        }
+        stats
+    }
+
+    /// Stats of the games played in `cod_version` that `store` finds for `query` and `tag_filter`
+    /// keeps.
+    pub fn for_query(
+        store: &mut dyn GameStore,
+        cod_version: &CodVersion,
+        query: &GameQuery,
+        tag_filter: &TagFilter,
+    ) -> Result<Self, Error> {
+        let games = tag_filter.apply(&store.query(query)?);
+        Ok(Self::from_games(filter_by_version(&games, cod_version)))
+    }
+
+    pub fn add_win(&mut self, map: &GunfightMap) {
+        self.map_stats.entry(map.clone()).or_default().wins += 1;
+        self.wins += 1;
+        self.last_was_win = true;
+        self.win_streak += 1;
+        if self.high_win_streak < self.win_streak {
+            self.high_win_streak = self.win_streak;
+        }
+        if self.high_loss_streak < self.loss_streak {
+            self.high_loss_streak = self.loss_streak;
+        }
+        self.loss_streak = 0;
+    }
+
+    pub fn add_loss(&mut self, map: &GunfightMap) {
+        self.map_stats.entry(map.clone()).or_default().losses += 1;
+        self.losses += 1;
+        self.last_was_win = false;
+        self.loss_streak += 1;
+        if self.high_loss_streak < self.loss_streak {
+            self.high_loss_streak = self.loss_streak;
+        }
+        if self.high_win_streak < self.win_streak {
+            self.high_win_streak = self.win_streak;
+        }
+        self.win_streak = 0;
+    }
+
+    /// Counts the round score and combat stats of `game`, after its result was added.
+    pub fn add_details(&mut self, game: &GamePlayed) {
+        self.rounds.add(game);
+        self.combat.add(game);
+        self.map_stats
+            .entry(game.map.clone())
+            .or_default()
+            .combat
+            .add(game);
+    }
+
     pub const fn get_all_map_stats(&self) -> &HashMap<GunfightMap, MapStats> {
         &self.map_stats
     }
@@ -33,80 +163,59 @@ impl StatsGroup {
         self.map_stats.get(map)
     }
 
+    /// Map stats ordered from the highest to the lowest win percentage, ties by map name.
+    pub fn get_sorted_map_stats(&self) -> Vec<(&GunfightMap, &MapStats)> {
+        self.get_map_stats_sorted_by(MapSort::WinPercentage)
+    }
+
+    /// Map stats ordered best first by `sort`, ties by map name.
+    pub fn get_map_stats_sorted_by(&self, sort: MapSort) -> Vec<(&GunfightMap, &MapStats)> {
+        let key = |map_stats: &MapStats| match sort {
+            MapSort::WinPercentage => map_stats.get_win_percentage(),
+            MapSort::LowerBound => map_stats.get_win_interval().lower,
+        };
+        let mut map_stats = self.map_stats.iter().collect::<Vec<_>>();
+        map_stats.sort_by(|a, b| {
+            key(b.1)
+                .total_cmp(&key(a.1))
+                .then_with(|| a.0.to_string().cmp(&b.0.to_string()))
+        });
+        map_stats
+    }
+
     pub fn get_win_percentage(&self) -> f32 {
         if self.wins + self.losses == 0 {
             return 0.0;
         }
         (self.wins as f32 / (self.wins + self.losses) as f32) * 100.0
     }
+
+    pub fn get_win_interval(&self) -> WinInterval {
+        WinInterval::wilson(self.wins, self.losses)
+    }
 }
 
 impl Stats {
+    /// Stats of `games`, where today is the gaming night `now` falls in.
     pub fn new(
         games: &[GamePlayed],
-        today: DateTime<Local>,
+        now: DateTime<Local>,
         cod_version: &CodVersion,
+        day_boundary: &DayBoundary,
     ) -> Result<Self, Error> {
-        let mut filtered_games: Vec<_> = match cod_version {
-            CodVersion::MW => games
-                .iter()
-                .filter(|g| {
-                    if GunfightMap::is_mw(&g.map) {
-                        true
-                    } else {
-                        log::error!(
-                            "Filtering map [{map}] that is not supported in [{cod_version}].",
-                            map = g.map
-                        );
-                        false
-                    }
-                })
-                .collect(),
-            CodVersion::MW3 => games
-                .iter()
-                .filter(|g| {
-                    if GunfightMap::is_mw3(&g.map) {
-                        true
-                    } else {
-                        log::error!(
-                            "Filtering map [{map}] that is not supported in [{cod_version}].",
-                            map = g.map
-                        );
-                        false
-                    }
-                })
-                .collect(),
-        };
+        let mut filtered_games = filter_by_version(games, cod_version);
         let mut stats = Self {
-            lifet: StatsGroup {
-                wins: 0,
-                losses: 0,
-                high_win_streak: 0,
-                high_loss_streak: 0,
-                win_streak: 0,
-                loss_streak: 0,
-                last_was_win: true,
-                map_stats: HashMap::new(),
-            },
-            today: StatsGroup {
-                wins: 0,
-                losses: 0,
-                high_win_streak: 0,
-                high_loss_streak: 0,
-                win_streak: 0,
-                loss_streak: 0,
-                last_was_win: true,
-                map_stats: HashMap::new(),
-            },
+            lifet: StatsGroup::default(),
+            today: StatsGroup::default(),
         };
-        let today = today.format(DAY_FMT).to_string();
+        let today = day_boundary.day_of(&now);
         let errors = filtered_games
             .iter_mut()
             .map(|game| {
                 if game.did_win {
-                    stats.add_win(game, &today)
+                    stats.add_win(game, today, day_boundary)
                 } else {
-                    stats.add_loss(game, &today)
+                    stats.add_loss(game, today, day_boundary)
                 }
             })
             .filter_map(|r| match r {
@@ -125,146 +234,54 @@ impl Stats {
         Ok(stats)
     }
 
-    pub fn add_win(&mut self, game: &GamePlayed, today: &str) -> Result<(), Error> {
-        if game.date_time.format(DAY_FMT).to_string() == today {
-            if self.today.map_stats.contains_key(&game.map) {
-                self.today
-                    .map_stats
-                    .get_mut(&game.map)
-                    .ok_or(Error::GunfightMapNotFound(game.map.clone()))?
-                    .wins += 1;
-            } else {
-                self.today
-                    .map_stats
-                    .insert(game.map.clone(), MapStats { wins: 1, losses: 0 });
-            }
-            self.today.wins += 1;
-            self.today.last_was_win = true;
-            if self.today.last_was_win {
-                self.today.win_streak += 1;
-                if self.today.high_win_streak < self.today.win_streak {
-                    self.today.high_win_streak = self.today.win_streak;
-                }
-                if self.today.high_loss_streak < self.today.loss_streak {
-                    self.today.high_loss_streak = self.today.loss_streak;
-                }
-                self.today.loss_streak = 0;
-            }
-        }
-        if self.lifet.map_stats.contains_key(&game.map) {
-            self.lifet
-                .map_stats
-                .get_mut(&game.map)
-                .ok_or(Error::GunfightMapNotFound(game.map.clone()))?
-                .wins += 1;
-        } else {
-            self.lifet
-                .map_stats
-                .insert(game.map.clone(), MapStats { wins: 1, losses: 0 });
-        }
-        self.lifet.wins += 1;
-        self.lifet.last_was_win = true;
-        if self.lifet.last_was_win {
-            self.lifet.win_streak += 1;
-            if self.lifet.high_win_streak < self.lifet.win_streak {
-                self.lifet.high_win_streak = self.lifet.win_streak;
-            }
-            if self.lifet.high_loss_streak < self.lifet.loss_streak {
-                self.lifet.high_loss_streak = self.lifet.loss_streak;
-            }
-            self.lifet.loss_streak = 0;
+    pub fn add_win(
+        &mut self,
+        game: &GamePlayed,
+        today: NaiveDate,
+        day_boundary: &DayBoundary,
+    ) -> Result<(), Error> {
+        if day_boundary.day_of(&game.date_time) == today {
+            self.today.add_win(&game.map);
+            self.today.add_details(game);
         }
+        self.lifet.add_win(&game.map);
+        self.lifet.add_details(game);
         Ok(())
     }
 
-    pub fn add_loss(&mut self, game: &GamePlayed, today: &str) -> Result<(), Error> {
-        if game.date_time.format(DAY_FMT).to_string() == today {
-            if self.today.map_stats.contains_key(&game.map) {
-                self.today
-                    .map_stats
-                    .get_mut(&game.map)
-                    .ok_or(Error::GunfightMapNotFound(game.map.clone()))?
-                    .losses += 1;
-            } else {
-                self.today
-                    .map_stats
-                    .insert(game.map.clone(), MapStats { wins: 0, losses: 1 });
-            }
-            self.today.losses += 1;
-            self.today.last_was_win = false;
-            if !self.today.last_was_win {
-                self.today.loss_streak += 1;
-                if self.today.high_loss_streak < self.today.loss_streak {
-                    self.today.high_loss_streak = self.today.loss_streak;
-                }
-                if self.today.high_win_streak < self.today.win_streak {
-                    self.today.high_win_streak = self.today.win_streak;
-                }
-                self.today.win_streak = 0;
-            }
-        }
-        if self.lifet.map_stats.contains_key(&game.map) {
-            self.lifet
-                .map_stats
-                .get_mut(&game.map)
-                .ok_or(Error::GunfightMapNotFound(game.map.clone()))?
-                .losses += 1;
-        } else {
-            self.lifet
-                .map_stats
-                .insert(game.map.clone(), MapStats { wins: 0, losses: 1 });
-        }
-        self.lifet.losses += 1;
-        self.lifet.last_was_win = false;
-        if !self.lifet.last_was_win {
-            self.lifet.loss_streak += 1;
-            if self.lifet.high_loss_streak < self.lifet.loss_streak {
-                self.lifet.high_loss_streak = self.lifet.loss_streak;
-            }
-            if self.lifet.high_win_streak < self.lifet.win_streak {
-                self.lifet.high_win_streak = self.lifet.win_streak;
-            }
-            self.lifet.win_streak = 0;
+    pub fn add_loss(
+        &mut self,
+        game: &GamePlayed,
+        today: NaiveDate,
+        day_boundary: &DayBoundary,
+    ) -> Result<(), Error> {
+        if day_boundary.day_of(&game.date_time) == today {
+            self.today.add_loss(&game.map);
+            self.today.add_details(game);
         }
+        self.lifet.add_loss(&game.map);
+        self.lifet.add_details(game);
         Ok(())
     }
 
-    pub fn display_map_stats(&self) {
+    pub fn display_map_stats(&self, sort: MapSort) {
         println!();
         println!("Lifetime:\n---");
-        let mut life_map_stats = self
-            .lifet
-            .get_all_map_stats()
+        self.lifet
+            .get_map_stats_sorted_by(sort)
             .iter()
-            .collect::<Vec<_>>()
-            .clone();
-
-        life_map_stats.sort_by(|a, b| {
-            b.1.get_win_percentage()
-                .total_cmp(&a.1.get_win_percentage())
-        });
-
-        life_map_stats.iter().for_each(|m| {
-            println!("{}: {}", m.0, m.1);
-        });
+            .for_each(|m| {
+                println!("{}: {}", m.0, m.1);
+            });
 
         println!();
         println!("Today:\n---");
-        let mut today_map_stats = self
-            .today
-            .get_all_map_stats()
+        self.today
+            .get_map_stats_sorted_by(sort)
             .iter()
-            .collect::<Vec<_>>()
-            .clone();
-
-        today_map_stats.sort_by(|a, b| {
-            b.1.get_win_percentage()
-                .total_cmp(&a.1.get_win_percentage())
-        });
-
-        today_map_stats.iter().for_each(|m| {
-            println!("{}: {}", m.0, m.1);
-        });
+            .for_each(|m| {
+                println!("{}: {}", m.0, m.1);
+            });
         println!();
     }
 }
@@ -288,10 +305,10 @@ mod tests {
 
         // Act / Assert
         assert_eq!(
-            Stats::new(&games, Local::now(),                &CodVersion::MW, )?,
+            Stats::new(&games, Local::now(),                &CodVersion::MW, &DayBoundary::default())?,
             Stats {
-                lifet: StatsGroup {wins:0,losses:0,high_win_streak:0,high_loss_streak:0,win_streak:0,loss_streak:0,last_was_win:true, map_stats: HashMap::new() },
-                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0,  win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: HashMap::new() },
+                lifet: StatsGroup {wins:0,losses:0,high_win_streak:0,high_loss_streak:0,win_streak:0,loss_streak:0,last_was_win:true, map_stats: HashMap::new(), ..Default::default() },
+                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0,  win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: HashMap::new(), ..Default::default() },
             },
         );
 
@@ -302,11 +319,11 @@ mod tests {
     fn test_stats_all_one_not_today() -> Result<()> {
         // Arrange
         let games: Vec<GamePlayed> = vec![
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), ..Default::default() },
         ];
         let mut maps_lifet = HashMap::new();
-        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 1, losses: 1 });
+        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 1, losses: 1, ..Default::default() });
         let maps_today = HashMap::new();
 
         // Act / Assert
@@ -315,10 +332,11 @@ mod tests {
                 &games,
                 Local.with_ymd_and_hms(2023, 9, 29, 0, 0, 0).unwrap(),
                 &CodVersion::MW,
+                &DayBoundary::default(),
             )?,
             Stats {
-                lifet: StatsGroup { wins: 1, losses: 1, high_win_streak: 1, high_loss_streak: 1,  win_streak: 1, loss_streak: 0, last_was_win: true, map_stats: maps_lifet },
-                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0,  win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: maps_today },
+                lifet: StatsGroup { wins: 1, losses: 1, high_win_streak: 1, high_loss_streak: 1,  win_streak: 1, loss_streak: 0, last_was_win: true, map_stats: maps_lifet, ..Default::default() },
+                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0,  win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: maps_today, ..Default::default() },
             },
         );
         Ok(())
@@ -328,12 +346,12 @@ mod tests {
     fn test_stats_add_win()  -> Result<()> {
         // Arrange
         let games: Vec<GamePlayed> = vec![
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), ..Default::default() },
         ];
         let mut maps_lifet = HashMap::new();
-        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 2, losses: 1 });
-        maps_lifet.insert(GunfightMap::Docks, MapStats { wins: 1, losses: 0 });
+        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 2, losses: 1, ..Default::default() });
+        maps_lifet.insert(GunfightMap::Docks, MapStats { wins: 1, losses: 0, ..Default::default() });
         let maps_today = HashMap::new();
 
         // Act
@@ -341,21 +359,23 @@ mod tests {
             &games,
             Local.with_ymd_and_hms(2023, 9, 29, 0, 0, 0).unwrap(),
             &CodVersion::MW,
+            &DayBoundary::default(),
         )?;
         stats.add_win(
-            &GamePlayed { map: GunfightMap::Asile9, did_win: true, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 3).unwrap(), },
-            "9-29-2023")?;
+            &GamePlayed { map: GunfightMap::Asile9, did_win: true, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 3).unwrap(), ..Default::default() },
+            NaiveDate::from_ymd_opt(2023, 9, 29).unwrap(), &DayBoundary::default())?;
         stats.add_win(
-            &GamePlayed { map: GunfightMap::Docks, did_win: true, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 4).unwrap(), },
-            "9-29-2023",
+            &GamePlayed { map: GunfightMap::Docks, did_win: true, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 4).unwrap(), ..Default::default() },
+            NaiveDate::from_ymd_opt(2023, 9, 29).unwrap(),
+            &DayBoundary::default(),
         )?;
 
         // Assert
         assert_eq!(
             stats,
             Stats {
-                lifet: StatsGroup { wins: 3, losses: 1, high_win_streak: 3, high_loss_streak: 1, win_streak: 3, loss_streak: 0, last_was_win: true, map_stats: maps_lifet },
-                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0, win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: maps_today },
+                lifet: StatsGroup { wins: 3, losses: 1, high_win_streak: 3, high_loss_streak: 1, win_streak: 3, loss_streak: 0, last_was_win: true, map_stats: maps_lifet, ..Default::default() },
+                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0, win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: maps_today, ..Default::default() },
             },
         );
         Ok(())
@@ -365,13 +385,13 @@ mod tests {
     fn test_stats_add_loss()  -> Result<(), Error> {
         // Arrange
         let games: Vec<GamePlayed> = vec![
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 3).unwrap(), },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 3).unwrap(), ..Default::default() },
         ];
         let mut maps_lifet = HashMap::new();
-        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 2, losses: 2 });
-        maps_lifet.insert(GunfightMap::Docks, MapStats { wins: 0, losses: 1 });
+        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 2, losses: 2, ..Default::default() });
+        maps_lifet.insert(GunfightMap::Docks, MapStats { wins: 0, losses: 1, ..Default::default() });
         let maps_today = HashMap::new();
 
         // Act
@@ -379,22 +399,25 @@ mod tests {
             &games,
             Local.with_ymd_and_hms(2023, 9, 29, 0, 0, 0).unwrap(),
             &CodVersion::MW,
+            &DayBoundary::default(),
         )?;
         stats.add_loss(
-            &GamePlayed { map: GunfightMap::Asile9, did_win: false, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 4).unwrap(), },
-            "9-29-2023",
+            &GamePlayed { map: GunfightMap::Asile9, did_win: false, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 4).unwrap(), ..Default::default() },
+            NaiveDate::from_ymd_opt(2023, 9, 29).unwrap(),
+            &DayBoundary::default(),
         )?;
         stats.add_loss(
-            &GamePlayed { map: GunfightMap::Docks, did_win: false, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 5).unwrap(), },
-            "9-29-2023",
+            &GamePlayed { map: GunfightMap::Docks, did_win: false, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 5).unwrap(), ..Default::default() },
+            NaiveDate::from_ymd_opt(2023, 9, 29).unwrap(),
+            &DayBoundary::default(),
         )?;
 
         // Assert
         assert_eq!(
             stats,
             Stats {
-                lifet: StatsGroup { wins: 2, losses: 3, high_win_streak: 2, high_loss_streak: 2, win_streak: 0, loss_streak: 2, last_was_win: false, map_stats: maps_lifet },
-                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0, win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: maps_today },
+                lifet: StatsGroup { wins: 2, losses: 3, high_win_streak: 2, high_loss_streak: 2, win_streak: 0, loss_streak: 2, last_was_win: false, map_stats: maps_lifet, ..Default::default() },
+                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0, win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: maps_today, ..Default::default() },
             },
         );
         Ok(())
@@ -404,16 +427,16 @@ mod tests {
     fn test_stats_curr_streak_across_days()  -> Result<(), Error> {
         // Arrange
         let games: Vec<GamePlayed> = vec![
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 3).unwrap(), },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 3).unwrap(), ..Default::default() },
             // New day
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 2).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 3).unwrap(), },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 2).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 3).unwrap(), ..Default::default() },
         ];
         let mut maps_lifet = HashMap::new();
-        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 5, losses: 1 });
-        maps_lifet.insert(GunfightMap::Docks, MapStats { wins: 0, losses: 1 });
+        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 5, losses: 1, ..Default::default() });
+        maps_lifet.insert(GunfightMap::Docks, MapStats { wins: 0, losses: 1, ..Default::default() });
         let maps_today = HashMap::new();
 
         // Act
@@ -421,22 +444,25 @@ mod tests {
             &games,
             Local.with_ymd_and_hms(2023, 9, 29, 0, 0, 0).unwrap(),
             &CodVersion::MW,
+            &DayBoundary::default(),
         )?;
         stats.add_win(
-            &GamePlayed { map: GunfightMap::Asile9, did_win: true, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 4).unwrap(), },
-            "9-29-2023",
+            &GamePlayed { map: GunfightMap::Asile9, did_win: true, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 4).unwrap(), ..Default::default() },
+            NaiveDate::from_ymd_opt(2023, 9, 29).unwrap(),
+            &DayBoundary::default(),
         )?;
         stats.add_loss(
-            &GamePlayed { map: GunfightMap::Docks, did_win: false, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 5).unwrap(), },
-            "9-29-2023",
+            &GamePlayed { map: GunfightMap::Docks, did_win: false, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 5).unwrap(), ..Default::default() },
+            NaiveDate::from_ymd_opt(2023, 9, 29).unwrap(),
+            &DayBoundary::default(),
         )?;
 
         // Assert
         assert_eq!(
             stats,
             Stats {
-                lifet: StatsGroup { wins: 5, losses: 2, high_win_streak: 5, high_loss_streak: 1, win_streak: 0, loss_streak: 1, last_was_win: false, map_stats: maps_lifet },
-                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0, win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: maps_today },
+                lifet: StatsGroup { wins: 5, losses: 2, high_win_streak: 5, high_loss_streak: 1, win_streak: 0, loss_streak: 1, last_was_win: false, map_stats: maps_lifet, ..Default::default() },
+                today: StatsGroup { wins: 0, losses: 0, high_win_streak: 0, high_loss_streak: 0, win_streak: 0, loss_streak: 0, last_was_win: true, map_stats: maps_today, ..Default::default() },
             },
         );
         Ok(())
@@ -446,38 +472,38 @@ mod tests {
     fn test_stats_all_one_today()  -> Result<(), Error>{
         // Arrange
         let games: Vec<GamePlayed> = vec![
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 3).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 4).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 5).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 6).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 7).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 8).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 9).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 10).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 11).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 12).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 13).unwrap(), },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 3).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 4).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 5).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 6).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 7).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 8).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 9).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 10).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 11).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 12).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 13).unwrap(), ..Default::default() },
             // Different day to test multiday
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 1).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 2).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 3).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 4).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 5).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 6).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 7).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 8).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 9).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 10).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 11).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 12).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 13).unwrap(), },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 1).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 2).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 3).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 4).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 5).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 6).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 7).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 8).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 9).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 10).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 11).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 12).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 13).unwrap(), ..Default::default() },
         ];
         let mut maps_lifet = HashMap::new();
-        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 18, losses: 8 });
+        maps_lifet.insert(GunfightMap::Asile9, MapStats { wins: 18, losses: 8, ..Default::default() });
         let mut maps_today = HashMap::new();
-        maps_today.insert(GunfightMap::Asile9, MapStats { wins: 9, losses: 4 });
+        maps_today.insert(GunfightMap::Asile9, MapStats { wins: 9, losses: 4, ..Default::default() });
 
         // Act / Assert
         assert_eq!(
@@ -485,10 +511,11 @@ mod tests {
                 &games,
                 Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 0).unwrap(),
                 &CodVersion::MW,
+                &DayBoundary::default(),
             )?,
             Stats {
-                lifet: StatsGroup { wins: 18, losses: 8, high_win_streak: 6, high_loss_streak: 2, win_streak: 0, loss_streak: 1, last_was_win: false, map_stats: maps_lifet },
-                today: StatsGroup { wins: 9, losses: 4, high_win_streak: 4, high_loss_streak: 2, win_streak: 0, loss_streak: 1, last_was_win: false, map_stats: maps_today },
+                lifet: StatsGroup { wins: 18, losses: 8, high_win_streak: 6, high_loss_streak: 2, win_streak: 0, loss_streak: 1, last_was_win: false, map_stats: maps_lifet, ..Default::default() },
+                today: StatsGroup { wins: 9, losses: 4, high_win_streak: 4, high_loss_streak: 2, win_streak: 0, loss_streak: 1, last_was_win: false, map_stats: maps_today, ..Default::default() },
             },
         );
         Ok(())
@@ -498,11 +525,11 @@ mod tests {
     fn test_stats_get_map()  -> Result<(), Error> {
         // Arrange
         let games: Vec<GamePlayed> = vec![
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 2).unwrap(), },
-            GamePlayed { did_win: true, map: GunfightMap::Hill, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 3).unwrap(), },
-            GamePlayed { did_win: false, map: GunfightMap::GulagShowers, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 4).unwrap(), },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 1).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 26, 0, 0, 2).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 0, 0, 2).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Hill, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 3).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::GulagShowers, date_time: Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 4).unwrap(), ..Default::default() },
         ];
 
         // Act / Assert
@@ -511,10 +538,12 @@ mod tests {
                 &games,
                 Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 0).unwrap(),
                 &CodVersion::MW,
+                &DayBoundary::default(),
             )?.lifet.get_map_stats(&GunfightMap::Asile9),
             Some(&MapStats {
                 losses: 2,
                 wins: 1,
+                ..Default::default()
             }),
         );
         assert_eq!(
@@ -522,10 +551,12 @@ mod tests {
                 &games,
                 Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 0).unwrap(),
                 &CodVersion::MW,
+                &DayBoundary::default(),
             )?.lifet.get_map_stats(&GunfightMap::Hill),
             Some(&MapStats {
                 losses: 0,
                 wins: 1,
+                ..Default::default()
             }),
         );
         assert_eq!(
@@ -533,12 +564,67 @@ mod tests {
                 &games,
                 Local.with_ymd_and_hms(2023, 9, 28, 0, 0, 0).unwrap(),
                 &CodVersion::MW,
+                &DayBoundary::default(),
             )?.lifet.get_map_stats(&GunfightMap::GulagShowers),
             Some(&MapStats {
                 losses: 1,
                 wins: 0,
+                ..Default::default()
             }),
         );
         Ok(())
     }
+
+    #[test]
+    fn test_stats_today_past_midnight() -> Result<(), Error> {
+        // Arrange
+        let games: Vec<GamePlayed> = vec![
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 3, 0, 0).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Asile9, date_time: Local.with_ymd_and_hms(2023, 9, 27, 23, 0, 0).unwrap(), ..Default::default() },
+            GamePlayed { did_win: false, map: GunfightMap::Hill, date_time: Local.with_ymd_and_hms(2023, 9, 28, 1, 0, 0).unwrap(), ..Default::default() },
+        ];
+        let mut maps_today = HashMap::new();
+        maps_today.insert(GunfightMap::Asile9, MapStats { wins: 1, losses: 0, ..Default::default() });
+        maps_today.insert(GunfightMap::Hill, MapStats { wins: 0, losses: 1, ..Default::default() });
+
+        // Act
+        let stats = Stats::new(
+            &games,
+            Local.with_ymd_and_hms(2023, 9, 28, 2, 0, 0).unwrap(),
+            &CodVersion::MW,
+            &DayBoundary::new(4, None),
+        )?;
+
+        // Assert
+        assert_eq!(
+            stats.today,
+            StatsGroup { wins: 1, losses: 1, high_win_streak: 1, high_loss_streak: 1, win_streak: 0, loss_streak: 1, last_was_win: false, map_stats: maps_today, ..Default::default() },
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_sorted_map_stats() -> Result<()> {
+        // Arrange
+        let game = |did_win, map| GamePlayed { did_win, map, ..Default::default() };
+        let stats = StatsGroup::from_games(&[
+            game(false, GunfightMap::Rust),
+            game(true, GunfightMap::Rust),
+            // Ties with Rust on win percentage, sorted by name
+            game(true, GunfightMap::Meat),
+            game(false, GunfightMap::Meat),
+            game(true, GunfightMap::Hill),
+            game(false, GunfightMap::Stack),
+            // Ties with Hill on win percentage, but its lower bound is higher with more games
+            game(true, GunfightMap::Shipment),
+            game(true, GunfightMap::Shipment),
+            game(true, GunfightMap::Shipment),
+        ]);
+        let maps = |sorted: Vec<(&GunfightMap, &MapStats)>| sorted.into_iter().map(|(map, _)| map.clone()).collect::<Vec<_>>();
+
+        // Act / Assert
+        assert_eq!(maps(stats.get_sorted_map_stats()), vec![GunfightMap::Hill, GunfightMap::Shipment, GunfightMap::Meat, GunfightMap::Rust, GunfightMap::Stack]);
+        assert_eq!(maps(stats.get_map_stats_sorted_by(MapSort::LowerBound)), vec![GunfightMap::Shipment, GunfightMap::Hill, GunfightMap::Meat, GunfightMap::Rust, GunfightMap::Stack]);
+        Ok(())
+    }
 }
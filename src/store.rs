@@ -0,0 +1,76 @@
+use std::path::Path;
+
+use chrono::{DateTime, Local};
+
+use crate::{
+    error::Error, json_store::JsonStore, map::GunfightMap, run::GamePlayed,
+    sqlite_store::SqliteStore,
+};
+
+/// File extensions that are stored in an embedded SQLite database instead of a JSON file.
+const SQLITE_EXTENSIONS: &[&str] = &["db", "sqlite", "sqlite3"];
+
+/// Where recorded games are persisted.
+///
+/// Every change is written as soon as it is made, there is no separate save step.
+pub trait GameStore {
+    /// Every recorded game, oldest first.
+    fn load_all(&mut self) -> Result<Vec<GamePlayed>, Error>;
+
+    fn append(&mut self, game: &GamePlayed) -> Result<(), Error>;
+
+    /// Records many games at once, stores should override this when they can do it in one write.
+    fn append_all(&mut self, games: &[GamePlayed]) -> Result<(), Error> {
+        games.iter().try_for_each(|game| self.append(game))
+    }
+
+    /// Removes `game`, returns `false` when it was not recorded.
+    fn delete(&mut self, game: &GamePlayed) -> Result<bool, Error>;
+
+    /// Swaps `old` for `new`, returns `false` when `old` was not recorded.
+    fn replace(&mut self, old: &GamePlayed, new: &GamePlayed) -> Result<bool, Error>;
+
+    /// Recorded games matching `query`, oldest first.
+    fn query(&mut self, query: &GameQuery) -> Result<Vec<GamePlayed>, Error> {
+        Ok(self
+            .load_all()?
+            .into_iter()
+            .filter(|g| query.matches(g))
+            .collect())
+    }
+}
+
+/// Narrows down which games are returned from [`GameStore::query`], unset fields match everything.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct GameQuery {
+    /// Only games played at or after this time.
+    pub since: Option<DateTime<Local>>,
+    /// Only games played before this time.
+    pub until: Option<DateTime<Local>>,
+    pub map: Option<GunfightMap>,
+}
+
+impl GameQuery {
+    pub fn matches(&self, game: &GamePlayed) -> bool {
+        self.since.is_none_or(|since| game.date_time >= since)
+            && self.until.is_none_or(|until| game.date_time < until)
+            && self.map.as_ref().is_none_or(|map| game.map == *map)
+    }
+}
+
+/// Opens the store at `file_path`, picking the backend from the file extension. Only a SQLite
+/// database can be created, and only when `create` is set.
+pub fn open_store(file_path: &Path, create: bool) -> Result<Box<dyn GameStore>, Error> {
+    let is_sqlite = file_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| SQLITE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()));
+
+    if is_sqlite {
+        tracing::debug!(stats_path=?file_path, "opening sqlite store");
+        Ok(Box::new(SqliteStore::open(file_path, create)?))
+    } else {
+        tracing::debug!(stats_path=?file_path, "opening json store");
+        Ok(Box::new(JsonStore::open(file_path)?))
+    }
+}
@@ -0,0 +1,237 @@
+use std::collections::BTreeMap;
+
+use strum_macros::Display;
+
+use crate::run::GamePlayed;
+
+/// z score beyond which the number of streaks is unlikely to be chance, two sided 95 %.
+const SIGNIFICANT_Z: f64 = 1.96;
+
+/// What the streaks say about the games, from the Wald-Wolfowitz runs test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Display)]
+pub enum StreakVerdict {
+    /// Fewer and longer streaks than chance, wins follow wins and losses follow losses.
+    Momentum,
+    /// As streaky as flipping a coin that lands on win as often as we do.
+    Noise,
+    /// More and shorter streaks than chance, results tend to flip.
+    #[strum(serialize = "Bounce Back")]
+    BounceBack,
+    /// Too few games, or only wins or only losses.
+    #[strum(serialize = "Not Enough Games")]
+    NotEnoughGames,
+}
+
+/// How often a streak of one length happened, next to how often it would by chance.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StreakCount {
+    pub length: usize,
+    pub wins: usize,
+    pub expected_wins: f64,
+    pub losses: usize,
+    pub expected_losses: f64,
+}
+
+/// Every win and loss streak in a run of games.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreakDistribution {
+    pub wins: usize,
+    pub losses: usize,
+    /// Number of win streaks of each length.
+    pub win_streaks: BTreeMap<usize, usize>,
+    /// Number of loss streaks of each length.
+    pub loss_streaks: BTreeMap<usize, usize>,
+}
+
+impl StreakDistribution {
+    /// Splits `games`, ordered oldest first, into streaks. The ongoing streak counts as well.
+    pub fn new(games: &[&GamePlayed]) -> Self {
+        let mut win_streaks = BTreeMap::new();
+        let mut loss_streaks = BTreeMap::new();
+        for streak in games.chunk_by(|a, b| a.did_win == b.did_win) {
+            let counts = if streak.first().is_some_and(|g| g.did_win) {
+                &mut win_streaks
+            } else {
+                &mut loss_streaks
+            };
+            *counts.entry(streak.len()).or_insert(0) += 1;
+        }
+
+        Self {
+            wins: games.iter().filter(|g| g.did_win).count(),
+            losses: games.iter().filter(|g| !g.did_win).count(),
+            win_streaks,
+            loss_streaks,
+        }
+    }
+
+    pub const fn games(&self) -> usize {
+        self.wins + self.losses
+    }
+
+    /// Number of streaks, both wins and losses.
+    pub fn runs(&self) -> usize {
+        self.win_streaks.values().sum::<usize>() + self.loss_streaks.values().sum::<usize>()
+    }
+
+    pub fn average_win_streak(&self) -> f64 {
+        average_length(&self.win_streaks)
+    }
+
+    pub fn average_loss_streak(&self) -> f64 {
+        average_length(&self.loss_streaks)
+    }
+
+    /// Average win streak of random games with the same win percentage.
+    pub fn expected_average_win_streak(&self) -> f64 {
+        expected_average_length(self.wins, self.games())
+    }
+
+    /// Average loss streak of random games with the same win percentage.
+    pub fn expected_average_loss_streak(&self) -> f64 {
+        expected_average_length(self.losses, self.games())
+    }
+
+    /// Every streak length seen, with how many would be expected by chance.
+    pub fn counts(&self) -> Vec<StreakCount> {
+        let longest = self
+            .win_streaks
+            .keys()
+            .chain(self.loss_streaks.keys())
+            .max()
+            .copied()
+            .unwrap_or_default();
+        (1..=longest)
+            .map(|length| StreakCount {
+                length,
+                wins: self.win_streaks.get(&length).copied().unwrap_or_default(),
+                expected_wins: expected_streaks(self.wins, self.games(), length),
+                losses: self.loss_streaks.get(&length).copied().unwrap_or_default(),
+                expected_losses: expected_streaks(self.losses, self.games(), length),
+            })
+            .collect()
+    }
+
+    /// Number of streaks random games with the same wins and losses would have on average.
+    pub fn expected_runs(&self) -> f64 {
+        let games = self.games() as f64;
+        if games == 0.0 {
+            return 0.0;
+        }
+        2.0 * self.wins as f64 * self.losses as f64 / games + 1.0
+    }
+
+    /// How many standard deviations the number of streaks is away from chance, negative when
+    /// there are fewer and longer streaks. `None` when it can not be told.
+    pub fn runs_z_score(&self) -> Option<f64> {
+        let games = self.games() as f64;
+        let both = 2.0 * self.wins as f64 * self.losses as f64;
+        let variance = both * (both - games) / (games * games * (games - 1.0));
+        if variance.is_nan() || variance <= 0.0 {
+            return None;
+        }
+        Some((self.runs() as f64 - self.expected_runs()) / variance.sqrt())
+    }
+
+    pub fn verdict(&self) -> StreakVerdict {
+        match self.runs_z_score() {
+            None => StreakVerdict::NotEnoughGames,
+            Some(z) if z <= -SIGNIFICANT_Z => StreakVerdict::Momentum,
+            Some(z) if z >= SIGNIFICANT_Z => StreakVerdict::BounceBack,
+            Some(_) => StreakVerdict::Noise,
+        }
+    }
+}
+
+fn average_length(streaks: &BTreeMap<usize, usize>) -> f64 {
+    let count = streaks.values().sum::<usize>();
+    if count == 0 {
+        return 0.0;
+    }
+    streaks
+        .iter()
+        .map(|(length, count)| length * count)
+        .sum::<usize>() as f64
+        / count as f64
+}
+
+/// Average streak length of an outcome that happens `hits` times in `games` independent games.
+fn expected_average_length(hits: usize, games: usize) -> f64 {
+    if hits == 0 || hits == games {
+        return hits as f64;
+    }
+    1.0 / (1.0 - hits as f64 / games as f64)
+}
+
+/// Number of streaks of exactly `length` expected in `games` independent games of an outcome
+/// that happens `hits` times.
+fn expected_streaks(hits: usize, games: usize, length: usize) -> f64 {
+    if games == 0 || length > games {
+        return 0.0;
+    }
+    let p = hits as f64 / games as f64;
+    let q = 1.0 - p;
+    let streak = p.powi(length as i32);
+    if length == games {
+        return streak;
+    }
+    // Streaks at either end only need to be cut off on one side.
+    let edges = 2.0 * q * streak;
+    let middle = (games - length - 1) as f64 * q * q * streak;
+    edges + middle
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Duration, Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::map::GunfightMap;
+
+    fn games(results: &str) -> Vec<GamePlayed> {
+        let start = Local.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        results
+            .chars()
+            .enumerate()
+            .map(|(i, result)| GamePlayed { did_win: result == 'W', map: GunfightMap::Shipment, date_time: start + Duration::minutes(i as i64), ..Default::default() })
+            .collect()
+    }
+
+    #[test]
+    fn test_streak_distribution() -> Result<()> {
+        // Arrange
+        let games = games("WWWLWLLW");
+        let games = games.iter().collect::<Vec<_>>();
+
+        // Act
+        let streaks = StreakDistribution::new(&games);
+
+        // Assert
+        assert_eq!(streaks.win_streaks, BTreeMap::from([(1, 2), (3, 1)]));
+        assert_eq!(streaks.loss_streaks, BTreeMap::from([(1, 1), (2, 1)]));
+        assert_eq!(streaks.runs(), 5);
+        assert_eq!(streaks.average_win_streak(), 5.0 / 3.0);
+        assert_eq!(streaks.expected_runs(), 2.0 * 5.0 * 3.0 / 8.0 + 1.0);
+        assert_eq!(streaks.counts().len(), 3);
+        assert_eq!(streaks.verdict(), StreakVerdict::Noise);
+        Ok(())
+    }
+
+    #[test]
+    fn test_streak_verdicts() -> Result<()> {
+        // Arrange
+        let momentum = games(&"WWWWWWWWWWLLLLLLLLLL".repeat(2));
+        let bounce_back = games(&"WL".repeat(20));
+        let only_wins = games("WWWW");
+
+        // Act / Assert
+        assert_eq!(StreakDistribution::new(&momentum.iter().collect::<Vec<_>>()).verdict(), StreakVerdict::Momentum);
+        assert_eq!(StreakDistribution::new(&bounce_back.iter().collect::<Vec<_>>()).verdict(), StreakVerdict::BounceBack);
+        assert_eq!(StreakDistribution::new(&only_wins.iter().collect::<Vec<_>>()).verdict(), StreakVerdict::NotEnoughGames);
+        Ok(())
+    }
+}
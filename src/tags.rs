@@ -0,0 +1,124 @@
+use inquire::{Confirm, MultiSelect, Text};
+
+use crate::{error::Error, run::GamePlayed};
+
+/// Keeps the games with any of the `include` tags, when there are some, and none of the
+/// `exclude` tags.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct TagFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl TagFilter {
+    pub fn new(include: &[String], exclude: &[String]) -> Self {
+        Self {
+            include: parse_tags(&include.join(",")),
+            exclude: parse_tags(&exclude.join(",")),
+        }
+    }
+
+    pub fn matches(&self, game: &GamePlayed) -> bool {
+        (self.include.is_empty() || game.tags.iter().any(|tag| self.include.contains(tag)))
+            && !game.tags.iter().any(|tag| self.exclude.contains(tag))
+    }
+
+    /// The games of `games` that match, in the same order.
+    pub fn apply(&self, games: &[GamePlayed]) -> Vec<GamePlayed> {
+        games
+            .iter()
+            .filter(|game| self.matches(game))
+            .cloned()
+            .collect()
+    }
+}
+
+/// Splits a comma separated list of tags, which are kept lowercase so `Lag` and `lag` are one tag.
+pub fn parse_tags(s: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in s.split(',').map(|tag| tag.trim().to_lowercase()) {
+        if !tag.is_empty() && !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    tags
+}
+
+/// Every tag in `games`, most recently used first.
+pub fn known_tags(games: &[GamePlayed]) -> Vec<String> {
+    let mut known: Vec<String> = Vec::new();
+    for tag in games.iter().rev().flat_map(|g| &g.tags) {
+        if !known.contains(tag) {
+            known.push(tag.clone());
+        }
+    }
+    known
+}
+
+/// Asks for the tags and notes of `game`, after checking there are any to add.
+pub fn prompt_notes_and_tags(game: &mut GamePlayed, known_tags: &[String]) -> Result<(), Error> {
+    if !Confirm::new("Add notes or tags?")
+        .with_default(false)
+        .prompt()?
+    {
+        return Ok(());
+    }
+
+    let mut tags = if known_tags.is_empty() {
+        Vec::new()
+    } else {
+        MultiSelect::new("Tags?", known_tags.to_vec()).prompt()?
+    };
+    let others = Text::new("Other tags?")
+        .with_help_message("comma separated like lag, cheater, empty to skip")
+        .prompt()?;
+    for tag in parse_tags(&others) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    game.tags = tags;
+
+    let notes = Text::new("Notes?")
+        .with_help_message("empty to skip")
+        .prompt()?;
+    game.notes = Some(notes.trim().to_string()).filter(|notes| !notes.is_empty());
+    Ok(())
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::map::GunfightMap;
+
+    #[test]
+    fn test_tag_filter() -> Result<()> {
+        // Arrange
+        let game = |map, tags: &str| GamePlayed { map, did_win: true, tags: parse_tags(tags), ..Default::default() };
+        let games = [
+            game(GunfightMap::Shipment, ""),
+            game(GunfightMap::Rust, "Lag, cheater"),
+            game(GunfightMap::Meat, "new controller"),
+        ];
+        let maps = |games: Vec<GamePlayed>| games.into_iter().map(|g| g.map).collect::<Vec<_>>();
+
+        // Act
+        let without_lag = TagFilter::new(&[], &["lag".to_string()]);
+        let only_tagged = TagFilter::new(&["cheater,New Controller".to_string()], &[]);
+        let both = TagFilter::new(&["cheater".to_string(), "new controller".to_string()], &["LAG".to_string()]);
+
+        // Assert
+        assert_eq!(games[1].tags, vec!["lag".to_string(), "cheater".to_string()]);
+        assert_eq!(maps(TagFilter::default().apply(&games)), vec![GunfightMap::Shipment, GunfightMap::Rust, GunfightMap::Meat]);
+        assert_eq!(maps(without_lag.apply(&games)), vec![GunfightMap::Shipment, GunfightMap::Meat]);
+        assert_eq!(maps(only_tagged.apply(&games)), vec![GunfightMap::Rust, GunfightMap::Meat]);
+        assert_eq!(maps(both.apply(&games)), vec![GunfightMap::Meat]);
+        assert_eq!(known_tags(&games), vec!["new controller".to_string(), "lag".to_string(), "cheater".to_string()]);
+        Ok(())
+    }
+}
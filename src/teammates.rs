@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use inquire::{validator::Validation, Select, Text};
+
+use crate::{error::Error, map::GunfightMap, run::GamePlayed, stats::StatsGroup};
+
+/// Number of maps listed as the best ones with a teammate.
+pub const BEST_MAPS: usize = 3;
+
+/// Stats of the games played with one teammate.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TeammateStats {
+    pub teammate: String,
+    pub stats: StatsGroup,
+}
+
+impl TeammateStats {
+    /// Maps with the best win percentage together, best first.
+    pub fn best_maps(&self) -> Vec<&GunfightMap> {
+        self.stats
+            .get_sorted_map_stats()
+            .into_iter()
+            .take(BEST_MAPS)
+            .map(|(map, _)| map)
+            .collect()
+    }
+}
+
+/// Every teammate in `games`, most recently played with first.
+pub fn roster(games: &[GamePlayed]) -> Vec<String> {
+    let mut roster: Vec<String> = Vec::new();
+    for teammate in games.iter().rev().filter_map(|g| g.teammate.as_ref()) {
+        if !roster.contains(teammate) {
+            roster.push(teammate.clone());
+        }
+    }
+    roster
+}
+
+/// Stats per teammate of `games`, ordered oldest first. Teammates with the most games come first.
+pub fn teammate_stats(games: &[&GamePlayed]) -> Vec<TeammateStats> {
+    let mut by_teammate: HashMap<&str, Vec<&GamePlayed>> = HashMap::new();
+    for game in games {
+        if let Some(teammate) = &game.teammate {
+            by_teammate.entry(teammate).or_default().push(game);
+        }
+    }
+
+    let mut teammates = by_teammate
+        .into_iter()
+        .map(|(teammate, games)| TeammateStats {
+            teammate: teammate.to_string(),
+            stats: StatsGroup::from_games(games),
+        })
+        .collect::<Vec<_>>();
+    teammates.sort_by(|a, b| {
+        (b.stats.wins + b.stats.losses)
+            .cmp(&(a.stats.wins + a.stats.losses))
+            .then_with(|| a.teammate.cmp(&b.teammate))
+    });
+    teammates
+}
+
+enum TeammateChoice {
+    Known(String),
+    New,
+    Nobody,
+}
+
+impl std::fmt::Display for TeammateChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Known(teammate) => write!(f, "{teammate}"),
+            Self::New => write!(f, "New Teammate"),
+            Self::Nobody => write!(f, "Random / Skip"),
+        }
+    }
+}
+
+/// Asks who `game` was played with, picking from `roster` or adding someone new.
+pub fn prompt_teammate(game: &mut GamePlayed, roster: &[String]) -> Result<(), Error> {
+    let choices = roster
+        .iter()
+        .cloned()
+        .map(TeammateChoice::Known)
+        .chain([TeammateChoice::New, TeammateChoice::Nobody])
+        .collect();
+    game.teammate = match Select::new("Teammate?", choices).prompt()? {
+        TeammateChoice::Known(teammate) => Some(teammate),
+        TeammateChoice::New => Some(
+            Text::new("Teammate's name?")
+                .with_validator(|s: &str| {
+                    Ok(if s.trim().is_empty() {
+                        Validation::Invalid("a name is needed".into())
+                    } else {
+                        Validation::Valid
+                    })
+                })
+                .prompt()?
+                .trim()
+                .to_string(),
+        ),
+        TeammateChoice::Nobody => None,
+    };
+    Ok(())
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Duration, Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_teammate_stats() -> Result<()> {
+        // Arrange
+        let start = Local.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap();
+        let game = |minutes, did_win, map, teammate: Option<&str>| GamePlayed { did_win, map, date_time: start + Duration::minutes(minutes), teammate: teammate.map(ToString::to_string), ..Default::default() };
+        let games = [
+            game(0, true, GunfightMap::Shipment, Some("Ghost")),
+            game(10, false, GunfightMap::Rust, Some("Ghost")),
+            game(20, true, GunfightMap::Rust, Some("Soap")),
+            game(30, true, GunfightMap::Meat, None),
+            game(40, true, GunfightMap::Meat, Some("Ghost")),
+        ];
+
+        // Act
+        let teammates = teammate_stats(&games.iter().collect::<Vec<_>>());
+
+        // Assert
+        assert_eq!(roster(&games), vec!["Ghost".to_string(), "Soap".to_string()]);
+        assert_eq!(teammates.iter().map(|t| (t.teammate.as_str(), t.stats.wins, t.stats.losses)).collect::<Vec<_>>(), vec![("Ghost", 2, 1), ("Soap", 1, 0)]);
+        assert_eq!(teammates[0].best_maps(), vec![&GunfightMap::Meat, &GunfightMap::Shipment, &GunfightMap::Rust]);
+        Ok(())
+    }
+}
@@ -0,0 +1,136 @@
+use chrono::Duration;
+
+use crate::{
+    confidence::WinInterval,
+    run::GamePlayed,
+    sessions::{find_sessions, DEFAULT_SESSION_GAP_MINUTES},
+    stats::StatsGroup,
+};
+
+/// Most recent games of the session compared against the lifetime win percentage.
+const RECENT_GAMES: usize = 10;
+
+/// Fewer games than this in the session say too little to call it cold.
+const MIN_RECENT_GAMES: usize = 5;
+
+/// Losses in a row that are always worth a warning, however bad the worst streak has been.
+const MIN_LOSS_STREAK: usize = 3;
+
+/// Share of the longest loss streak ever at which the current one is worth a warning.
+const LOSS_STREAK_SHARE: f64 = 0.75;
+
+/// Why it is time for a break.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TiltReason {
+    /// The current loss streak is closing in on the longest one ever.
+    LossStreak { length: usize, record: usize },
+    /// Even the best case for the recent games of the session is below the lifetime win
+    /// percentage.
+    ColdSession {
+        games: usize,
+        win_percentage: f32,
+        lifetime_win_percentage: f32,
+    },
+}
+
+impl std::fmt::Display for TiltReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LossStreak { length, record } if length >= record => {
+                write!(
+                    f,
+                    "{length} L's in a row, the worst streak you have ever had"
+                )
+            }
+            Self::LossStreak { length, record } => {
+                write!(
+                    f,
+                    "{length} L's in a row, your worst streak ever is {record}"
+                )
+            }
+            Self::ColdSession {
+                games,
+                win_percentage,
+                lifetime_win_percentage,
+            } => write!(
+                f,
+                "{win_percentage:.0} % over the last {games} games, well below your usual \
+                 {lifetime_win_percentage:.0} %"
+            ),
+        }
+    }
+}
+
+/// Looks at the end of `games`, ordered oldest first, for signs the session has gone cold
+/// compared to `lifetime`.
+pub fn check_tilt(games: &[&GamePlayed], lifetime: &StatsGroup) -> Vec<TiltReason> {
+    let mut reasons = Vec::new();
+
+    if !lifetime.last_was_win {
+        let length = lifetime.loss_streak;
+        let record = lifetime.high_loss_streak;
+        if length >= MIN_LOSS_STREAK && length as f64 >= (record as f64 * LOSS_STREAK_SHARE).ceil()
+        {
+            reasons.push(TiltReason::LossStreak { length, record });
+        }
+    }
+
+    let sessions = find_sessions(games, Duration::minutes(DEFAULT_SESSION_GAP_MINUTES));
+    if let Some(session) = sessions.last() {
+        let recent = games
+            .iter()
+            .skip(games.len().saturating_sub(session.games.min(RECENT_GAMES)))
+            .copied();
+        let recent = StatsGroup::from_games(recent);
+        let played = recent.wins + recent.losses;
+        let lifetime_win_percentage = lifetime.get_win_percentage();
+        if played >= MIN_RECENT_GAMES
+            && WinInterval::wilson(recent.wins, recent.losses).upper < lifetime_win_percentage
+        {
+            reasons.push(TiltReason::ColdSession {
+                games: played,
+                win_percentage: recent.get_win_percentage(),
+                lifetime_win_percentage,
+            });
+        }
+    }
+
+    reasons
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::map::GunfightMap;
+
+    #[test]
+    fn test_check_tilt() -> Result<()> {
+        // Arrange
+        let at = |d, m| Local.with_ymd_and_hms(2024, 1, d, 20, m, 0).unwrap();
+        // A strong first night, then one win in eight on the second.
+        let mut games = (0..40)
+            .map(|i| GamePlayed { did_win: i % 4 != 0, map: GunfightMap::Shipment, date_time: at(1, i), ..Default::default() })
+            .collect::<Vec<_>>();
+        games.extend((0..8).map(|i| GamePlayed { did_win: i == 2, map: GunfightMap::Rust, date_time: at(2, i * 5), ..Default::default() }));
+        let games = games.iter().collect::<Vec<_>>();
+        let first_night = &games[..40];
+
+        // Act
+        let fine = check_tilt(first_night, &StatsGroup::from_games(first_night.iter().copied()));
+        let tilted = check_tilt(&games, &StatsGroup::from_games(games.iter().copied()));
+
+        // Assert
+        assert_eq!(fine, vec![]);
+        assert_eq!(tilted, vec![
+            TiltReason::LossStreak { length: 5, record: 5 },
+            TiltReason::ColdSession { games: 8, win_percentage: 12.5, lifetime_win_percentage: 31.0 / 48.0 * 100.0 },
+        ]);
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::{Datelike, Timelike, Weekday};
+
+use crate::{day_boundary::DayBoundary, map::MapStats, run::GamePlayed};
+
+pub const HOURS: usize = 24;
+pub const WEEKDAYS: [Weekday; 7] = [
+    Weekday::Mon,
+    Weekday::Tue,
+    Weekday::Wed,
+    Weekday::Thu,
+    Weekday::Fri,
+    Weekday::Sat,
+    Weekday::Sun,
+];
+
+/// Wins and losses bucketed by the weekday and the hour a game was played at.
+///
+/// Weekdays follow the day boundary, so with days starting at 4 a game at 1 in the night
+/// from Friday to Saturday is counted as Friday, hour 1.
+#[derive(Debug, PartialEq, Eq)]
+pub struct TimeOfDayStats {
+    /// Indexed by weekday, Monday first, then by hour of the day.
+    grid: [[MapStats; HOURS]; 7],
+    start_hour: u32,
+}
+
+impl TimeOfDayStats {
+    pub fn new(games: &[&GamePlayed], day_boundary: &DayBoundary) -> Self {
+        let mut grid: [[MapStats; HOURS]; 7] =
+            std::array::from_fn(|_| std::array::from_fn(|_| MapStats::default()));
+        for game in games {
+            let weekday = day_boundary.day_of(&game.date_time).weekday();
+            let hour = day_boundary.wall_clock(&game.date_time).hour() as usize;
+            let cell = &mut grid[weekday.num_days_from_monday() as usize][hour % HOURS];
+            if game.did_win {
+                cell.wins += 1;
+            } else {
+                cell.losses += 1;
+            }
+        }
+
+        Self {
+            grid,
+            start_hour: day_boundary.start_hour,
+        }
+    }
+
+    /// Hour the gaming day starts at.
+    pub const fn start_hour(&self) -> usize {
+        self.start_hour as usize
+    }
+
+    pub const fn get(&self, weekday: Weekday, hour: usize) -> &MapStats {
+        &self.grid[weekday.num_days_from_monday() as usize][hour % HOURS]
+    }
+
+    /// Every hour of the day with games played in it, starting with the hour days start at so
+    /// late nights come after the evening.
+    pub fn by_hour(&self) -> Vec<(usize, MapStats)> {
+        (0..HOURS)
+            .map(|i| (i + self.start_hour as usize) % HOURS)
+            .map(|hour| {
+                let stats = total(WEEKDAYS.iter().map(|weekday| self.get(*weekday, hour)));
+                (hour, stats)
+            })
+            .filter(|(_, stats)| stats.wins + stats.losses > 0)
+            .collect()
+    }
+
+    /// Every weekday with games played on it, Monday first.
+    pub fn by_weekday(&self) -> Vec<(Weekday, MapStats)> {
+        WEEKDAYS
+            .iter()
+            .map(|weekday| {
+                let stats = total((0..HOURS).map(|hour| self.get(*weekday, hour)));
+                (*weekday, stats)
+            })
+            .filter(|(_, stats)| stats.wins + stats.losses > 0)
+            .collect()
+    }
+}
+
+fn total<'a>(cells: impl Iterator<Item = &'a MapStats>) -> MapStats {
+    cells.fold(MapStats::default(), |total, cell| MapStats {
+        wins: total.wins + cell.wins,
+        losses: total.losses + cell.losses,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+    use crate::map::GunfightMap;
+
+    #[test]
+    fn test_buckets_follow_day_boundary() -> Result<()> {
+        // Arrange
+        let at = |d, h| Local.with_ymd_and_hms(2024, 1, d, h, 0, 0).unwrap();
+        let games = [
+            // Friday evening
+            GamePlayed { did_win: true, map: GunfightMap::Shipment, date_time: at(5, 21), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Rust, date_time: at(5, 21), ..Default::default() },
+            // Still Friday night, past midnight
+            GamePlayed { did_win: false, map: GunfightMap::Rust, date_time: at(6, 1), ..Default::default() },
+            // Saturday evening
+            GamePlayed { did_win: false, map: GunfightMap::Meat, date_time: at(6, 21), ..Default::default() },
+        ];
+        let games = games.iter().collect::<Vec<_>>();
+
+        // Act
+        let stats = TimeOfDayStats::new(&games, &DayBoundary::new(4, None));
+
+        // Assert
+        assert_eq!(stats.get(Weekday::Fri, 1), &MapStats { wins: 0, losses: 1, ..Default::default() });
+        assert_eq!(stats.by_hour(), vec![(21, MapStats { wins: 2, losses: 1, ..Default::default() }), (1, MapStats { wins: 0, losses: 1, ..Default::default() })]);
+        assert_eq!(stats.by_weekday(), vec![(Weekday::Fri, MapStats { wins: 2, losses: 1, ..Default::default() }), (Weekday::Sat, MapStats { wins: 0, losses: 1, ..Default::default() })]);
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use chrono::{Datelike, NaiveDate};
+use clap::ValueEnum;
+use strum_macros::{Display, EnumIter};
+
+use crate::{day_boundary::DayBoundary, map::GunfightMap, run::GamePlayed, stats::StatsGroup};
+
+/// How games are bucketed in the trend table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Display, EnumIter)]
+pub enum Period {
+    /// ISO weeks, Monday to Sunday.
+    Week,
+    Month,
+}
+
+impl Period {
+    /// Name of the period `day` falls in, e.g. `2024-W05` or `2024-01`.
+    pub fn label(self, day: NaiveDate) -> String {
+        match self {
+            Self::Week => {
+                let week = day.iso_week();
+                format!("{}-W{:02}", week.year(), week.week())
+            }
+            Self::Month => day.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+/// Stats of the games played in one week or month.
+#[derive(Debug, PartialEq, Eq)]
+pub struct PeriodStats {
+    pub period: String,
+    pub stats: StatsGroup,
+}
+
+impl PeriodStats {
+    /// The map with the most wins, ties go to the better win percentage.
+    pub fn top_map(&self) -> Option<&GunfightMap> {
+        // `max_by_key` keeps the last of equal keys, so walk from the worst win percentage up.
+        self.stats
+            .get_sorted_map_stats()
+            .into_iter()
+            .rev()
+            .max_by_key(|(_, map_stats)| map_stats.wins)
+            .map(|(map, _)| map)
+    }
+}
+
+/// Splits `games`, ordered oldest first, into the periods they were played in.
+pub fn group_by_period(
+    games: &[&GamePlayed],
+    period: Period,
+    day_boundary: &DayBoundary,
+) -> Vec<PeriodStats> {
+    let label = |game: &GamePlayed| period.label(day_boundary.day_of(&game.date_time));
+    games
+        .chunk_by(|a, b| label(a) == label(b))
+        .filter_map(|games| {
+            Some(PeriodStats {
+                period: label(games.first()?),
+                stats: StatsGroup::from_games(games.iter().copied()),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::{Local, TimeZone};
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_group_by_period() -> Result<()> {
+        // Arrange
+        let games = [
+            // Sunday of 2023-W52
+            GamePlayed { did_win: true, map: GunfightMap::Shipment, date_time: Local.with_ymd_and_hms(2023, 12, 31, 20, 0, 0).unwrap(), ..Default::default() },
+            // Monday of 2024-W01
+            GamePlayed { did_win: false, map: GunfightMap::Rust, date_time: Local.with_ymd_and_hms(2024, 1, 1, 20, 0, 0).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Meat, date_time: Local.with_ymd_and_hms(2024, 1, 2, 20, 0, 0).unwrap(), ..Default::default() },
+            GamePlayed { did_win: true, map: GunfightMap::Rust, date_time: Local.with_ymd_and_hms(2024, 1, 3, 20, 0, 0).unwrap(), ..Default::default() },
+        ];
+        let games = games.iter().collect::<Vec<_>>();
+
+        // Act
+        let weeks = group_by_period(&games, Period::Week, &DayBoundary::default());
+        let months = group_by_period(&games, Period::Month, &DayBoundary::default());
+
+        // Assert
+        assert_eq!(weeks.iter().map(|w| (w.period.as_str(), w.stats.wins, w.stats.losses)).collect::<Vec<_>>(), vec![("2023-W52", 1, 0), ("2024-W01", 2, 1)]);
+        assert_eq!(months.iter().map(|m| m.period.as_str()).collect::<Vec<_>>(), vec!["2023-12", "2024-01"]);
+        assert_eq!(weeks[1].top_map(), Some(&GunfightMap::Meat));
+        Ok(())
+    }
+}
@@ -0,0 +1,307 @@
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate};
+use inquire::{validator::Validation, CustomType, Select, Text};
+use strum::IntoEnumIterator;
+
+use crate::{
+    day_boundary::DayBoundary, error::Error, menus::TimeWindowOption, store::GameQuery, CodVersion,
+};
+
+/// Days each season of Modern Warfare (2019) started on, season 1 first.
+const MW_SEASONS: &[(i32, u32, u32)] = &[
+    (2019, 12, 3),
+    (2020, 2, 11),
+    (2020, 4, 8),
+    (2020, 6, 11),
+    (2020, 8, 5),
+    (2020, 9, 29),
+];
+
+/// Days each season of Modern Warfare III (2023) started on, season 1 first.
+const MW3_SEASONS: &[(i32, u32, u32)] = &[
+    (2023, 12, 6),
+    (2024, 2, 7),
+    (2024, 4, 3),
+    (2024, 5, 29),
+    (2024, 7, 24),
+];
+
+/// A span of time stats can be narrowed down to, on top of lifetime and today.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TimeWindow {
+    /// The last `n` days, counting today.
+    LastDays(u32),
+    /// Since Monday of the current week.
+    Week,
+    /// Since the first of the current month.
+    Month,
+    /// Between two points in time, either end can be left open.
+    Range {
+        since: Option<DayOrTime>,
+        until: Option<DayOrTime>,
+    },
+    /// A season of the selected version, counting from 1.
+    Season(usize),
+}
+
+impl std::fmt::Display for TimeWindow {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LastDays(1) => write!(f, "Last Day"),
+            Self::LastDays(days) => write!(f, "Last {days} Days"),
+            Self::Week => write!(f, "This Week"),
+            Self::Month => write!(f, "This Month"),
+            Self::Range { since, until } => {
+                write!(f, "Games")?;
+                if let Some(since) = since {
+                    write!(f, " since {since}")?;
+                }
+                if let Some(until) = until {
+                    write!(f, " until {until}")?;
+                }
+                Ok(())
+            }
+            Self::Season(season) => write!(f, "Season {season}"),
+        }
+    }
+}
+
+impl TimeWindow {
+    pub fn get_window_choice(cod_version: &CodVersion) -> Result<Self, Error> {
+        let window =
+            match Select::new("Which games?", TimeWindowOption::iter().collect()).prompt()? {
+                TimeWindowOption::LastDays => Self::LastDays(
+                    CustomType::new("How many days?")
+                        .with_default(7)
+                        .with_validator(|days: &u32| {
+                            Ok(if *days == 0 {
+                                Validation::Invalid("count at least one day".into())
+                            } else {
+                                Validation::Valid
+                            })
+                        })
+                        .prompt()?,
+                ),
+                TimeWindowOption::Week => Self::Week,
+                TimeWindowOption::Month => Self::Month,
+                TimeWindowOption::Season => {
+                    let seasons = (1..=season_count(cod_version)).collect::<Vec<_>>();
+                    let latest = seasons.len().saturating_sub(1);
+                    Self::Season(
+                        Select::new("Which season?", seasons)
+                            .with_starting_cursor(latest)
+                            .prompt()?,
+                    )
+                }
+                TimeWindowOption::Range => Self::Range {
+                    since: prompt_day_or_time("Since?")?,
+                    until: prompt_day_or_time("Until?")?,
+                },
+            };
+        Ok(window)
+    }
+
+    /// The games that fall in this window, relative to `now`.
+    pub fn query(
+        &self,
+        now: DateTime<Local>,
+        cod_version: &CodVersion,
+        day_boundary: &DayBoundary,
+    ) -> Result<GameQuery, Error> {
+        let today = day_boundary.day_of(&now);
+        let (since, until) = match self {
+            // Going back further than the calendar does takes in every game.
+            Self::LastDays(days) => (
+                today
+                    .checked_sub_days(Days::new(u64::from(days.saturating_sub(1))))
+                    .map(|day| day_boundary.start_of(day)),
+                None,
+            ),
+            Self::Week => (
+                Some(day_boundary.start_of(
+                    today - Days::new(u64::from(today.weekday().num_days_from_monday())),
+                )),
+                None,
+            ),
+            Self::Month => (
+                Some(day_boundary.start_of(today.with_day(1).unwrap_or(today))),
+                None,
+            ),
+            Self::Range { since, until } => (
+                since.map(|since| since.resolve(day_boundary)),
+                until.map(|until| until.resolve(day_boundary)),
+            ),
+            Self::Season(season) => return season_query(*season, cod_version, day_boundary),
+        };
+        Ok(GameQuery {
+            since,
+            until,
+            ..Default::default()
+        })
+    }
+}
+
+/// Number of seasons known for `cod_version`.
+pub const fn season_count(cod_version: &CodVersion) -> usize {
+    seasons(cod_version).len()
+}
+
+const fn seasons(cod_version: &CodVersion) -> &'static [(i32, u32, u32)] {
+    match cod_version {
+        CodVersion::MW => MW_SEASONS,
+        CodVersion::MW3 => MW3_SEASONS,
+    }
+}
+
+/// From when `season` started until the one after it started, the latest season has no end.
+fn season_query(
+    season: usize,
+    cod_version: &CodVersion,
+    day_boundary: &DayBoundary,
+) -> Result<GameQuery, Error> {
+    let seasons = seasons(cod_version);
+    let start_of_season = |index: usize| {
+        seasons.get(index).and_then(|(year, month, day)| {
+            NaiveDate::from_ymd_opt(*year, *month, *day).map(|day| day_boundary.start_of(day))
+        })
+    };
+
+    let since = season
+        .checked_sub(1)
+        .and_then(start_of_season)
+        .ok_or_else(|| Error::SeasonNotFound(season, cod_version.clone()))?;
+    Ok(GameQuery {
+        since: Some(since),
+        until: start_of_season(season),
+        ..Default::default()
+    })
+}
+
+fn prompt_day_or_time(message: &str) -> Result<Option<DayOrTime>, Error> {
+    let answer = Text::new(message)
+        .with_help_message("a day like 2024-01-31 or an RFC 3339 timestamp, empty to leave open")
+        .with_validator(|s: &str| {
+            Ok(match s.trim() {
+                "" => Validation::Valid,
+                s => parse_day_or_time(s)
+                    .map_or_else(|e| Validation::Invalid(e.into()), |_| Validation::Valid),
+            })
+        })
+        .prompt()?;
+    match answer.trim() {
+        "" => Ok(None),
+        s => Ok(parse_day_or_time(s).ok()),
+    }
+}
+
+/// One end of a [`TimeWindow::Range`], a day is only turned into a time once the day boundary is
+/// known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DayOrTime {
+    Day(NaiveDate),
+    Time(DateTime<Local>),
+}
+
+impl DayOrTime {
+    /// The time this stands for, a day starts where `day_boundary` puts it.
+    pub fn resolve(self, day_boundary: &DayBoundary) -> DateTime<Local> {
+        match self {
+            Self::Day(day) => day_boundary.start_of(day),
+            Self::Time(time) => time,
+        }
+    }
+}
+
+impl std::fmt::Display for DayOrTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Day(day) => write!(f, "{}", day.format("%Y-%m-%d")),
+            Self::Time(time) => write!(f, "{}", time.to_rfc3339()),
+        }
+    }
+}
+
+/// Parses either a day like `2024-01-31`, meaning the start of that day, or an RFC 3339 timestamp.
+pub fn parse_day_or_time(s: &str) -> Result<DayOrTime, String> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Ok(DayOrTime::Day(date));
+    }
+    s.parse::<DateTime<Local>>()
+        .map(DayOrTime::Time)
+        .map_err(|_| format!("[{s}] is neither a day like 2024-01-31 nor an RFC 3339 timestamp"))
+}
+
+#[cfg(test)]
+#[rustfmt::skip]
+mod tests {
+    use anyhow::Result;
+
+    use chrono::TimeZone;
+    use pretty_assertions::assert_eq;
+
+    use super::*;
+
+    #[test]
+    fn test_window_query() -> Result<()> {
+        // Arrange
+        let now = Local.with_ymd_and_hms(2024, 2, 15, 18, 30, 0).unwrap();
+        let day = |d| Some(Local.with_ymd_and_hms(2024, 2, d, 0, 0, 0).unwrap());
+
+        // Act
+        let last_days = TimeWindow::LastDays(7).query(now, &CodVersion::MW3, &DayBoundary::default())?;
+        let week = TimeWindow::Week.query(now, &CodVersion::MW3, &DayBoundary::default())?;
+        let month = TimeWindow::Month.query(now, &CodVersion::MW3, &DayBoundary::default())?;
+
+        // Assert
+        assert_eq!((last_days.since, last_days.until), (day(9), None));
+        assert_eq!((week.since, week.until), (day(12), None));
+        assert_eq!((month.since, month.until), (day(1), None));
+        Ok(())
+    }
+
+    #[test]
+    fn test_last_days_past_the_calendar() -> Result<()> {
+        // Arrange
+        let now = Local.with_ymd_and_hms(2024, 2, 15, 18, 30, 0).unwrap();
+
+        // Act
+        let query = TimeWindow::LastDays(u32::MAX).query(now, &CodVersion::MW3, &DayBoundary::new(4, None))?;
+
+        // Assert
+        assert_eq!((query.since, query.until), (None, None));
+        Ok(())
+    }
+
+    #[test]
+    fn test_season_query() -> Result<()> {
+        // Arrange
+        let now = Local.with_ymd_and_hms(2024, 2, 15, 18, 30, 0).unwrap();
+
+        // Act
+        let first = TimeWindow::Season(1).query(now, &CodVersion::MW3, &DayBoundary::default())?;
+        let latest = TimeWindow::Season(season_count(&CodVersion::MW3)).query(now, &CodVersion::MW3, &DayBoundary::default())?;
+
+        // Assert
+        assert_eq!(first.since, Some(Local.with_ymd_and_hms(2023, 12, 6, 0, 0, 0).unwrap()));
+        assert_eq!(first.until, Some(Local.with_ymd_and_hms(2024, 2, 7, 0, 0, 0).unwrap()));
+        assert_eq!(latest.until, None);
+        assert!(matches!(TimeWindow::Season(0).query(now, &CodVersion::MW3, &DayBoundary::default()), Err(Error::SeasonNotFound(0, _))));
+        Ok(())
+    }
+
+    #[test]
+    fn test_range_days_follow_day_boundary() -> Result<()> {
+        // Arrange
+        let now = Local.with_ymd_and_hms(2024, 2, 15, 18, 30, 0).unwrap();
+        let at = Local.with_ymd_and_hms(2024, 2, 10, 12, 0, 0).unwrap();
+        let range = TimeWindow::Range { since: Some(parse_day_or_time("2024-02-05").map_err(anyhow::Error::msg)?), until: Some(parse_day_or_time(&at.to_rfc3339()).map_err(anyhow::Error::msg)?) };
+
+        // Act
+        let query = range.query(now, &CodVersion::MW3, &DayBoundary::new(4, None))?;
+
+        // Assert
+        assert_eq!(query.since, Some(Local.with_ymd_and_hms(2024, 2, 5, 4, 0, 0).unwrap()));
+        assert_eq!(query.until, Some(at));
+        assert_eq!(range.to_string(), format!("Games since 2024-02-05 until {}", at.to_rfc3339()));
+        Ok(())
+    }
+}
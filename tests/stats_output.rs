@@ -0,0 +1,56 @@
+use std::{path::PathBuf, process::Command};
+
+use anyhow::Result;
+
+/// Copy of the sample stat sheet, so running the binary can not change it.
+fn stat_sheet(name: &str) -> Result<PathBuf> {
+    let path = std::env::temp_dir().join(format!("cod_keeper_{name}_{}.json", std::process::id()));
+    std::fs::copy("stat_sheet_real_mw3.json", &path)?;
+    Ok(path)
+}
+
+/// Runs the binary with `args` after the stat sheet and version, returning stdout.
+fn run(name: &str, verbosity: &[&str], args: &[&str]) -> Result<String> {
+    let path = stat_sheet(name)?;
+    let output = Command::new(env!("CARGO_BIN_EXE_cod_keeper"))
+        .env_remove("RUST_LOG")
+        .args(verbosity)
+        .arg("--stats-path")
+        .arg(&path)
+        .args(["--cod-version", "mw3"])
+        .args(args)
+        .output()?;
+    std::fs::remove_file(&path)?;
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    Ok(String::from_utf8(output.stdout)?)
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_stats_json_stdout_parses() -> Result<()> {
+    // Act / Assert
+    for verbosity in [&[][..], &["-vvv"], &["-q"]] {
+        let stdout = run("stats_json", verbosity, &["stats", "--format", "json"])?;
+        let report: serde_json::Value = serde_json::from_str(&stdout)?;
+        assert!(report["lifetime"]["wins"].is_u64());
+    }
+    Ok(())
+}
+
+#[test]
+#[rustfmt::skip]
+fn test_other_formats_stdout_parses() -> Result<()> {
+    // Act / Assert
+    serde_yaml::from_str::<serde_yaml::Value>(&run("stats_yaml", &["-vvv"], &["stats", "--format", "yaml"])?)?;
+    serde_json::from_str::<serde_json::Value>(&run("sessions_json", &["-vvv"], &["sessions", "--format", "json"])?)?;
+    let csv = run("stats_csv", &["-vvv"], &["stats", "--format", "csv"])?;
+    assert!(csv.starts_with("group,map,wins,losses"));
+    for record in csv::Reader::from_reader(csv.as_bytes()).records() {
+        record?;
+    }
+    Ok(())
+}